@@ -0,0 +1,377 @@
+//! Compare hash logs written by `easy_hash::record::HashRecorder`.
+//!
+//! Records are aligned by frame and label; frames are expected to be
+//! non-decreasing within a log. The tool reports the first frame at which the
+//! logs diverge, with the divergent field paths of every label that recorded its
+//! hash tree on both sides.
+//!
+//! With `--bisect`, the first log is taken as the reference and the others as
+//! runs in order (e.g. one per commit). Assuming that once a run diverges all
+//! later runs do too, the first divergent run is found by binary search.
+//!
+//! Exits with 0 if the logs match, 1 if they diverge and 2 on errors.
+
+use std::fmt::Write as _;
+use std::io;
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use easy_hash::record::{Record, RecordReader};
+use easy_hash::tree::HashDiff;
+
+const USAGE: &str = "\
+Usage: ehash-diff [--json] <LEFT> <RIGHT>
+       ehash-diff [--json] --bisect <REFERENCE> <RUN>...
+
+Options:
+  --json     Print the result as JSON
+  --bisect   Find the first run that diverges from the reference
+  -h, --help Print this help";
+
+struct Args {
+    json: bool,
+    bisect: bool,
+    logs: Vec<PathBuf>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        json: false,
+        bisect: false,
+        logs: Vec::new(),
+    };
+    for arg in args {
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--bisect" => parsed.bisect = true,
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => parsed.logs.push(PathBuf::from(arg)),
+        }
+    }
+
+    match (parsed.bisect, parsed.logs.len()) {
+        (false, 2) | (true, 2..) => Ok(Some(parsed)),
+        (false, _) => Err("expected exactly two logs".to_string()),
+        (true, _) => Err("expected a reference log and at least one run".to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = if args.bisect {
+        bisect(&args.logs).map(|report| {
+            let diverged = report.is_some();
+            let output = match args.json {
+                true => bisect_json(&args.logs, report.as_ref()),
+                false => bisect_text(&args.logs, report.as_ref()),
+            };
+            (diverged, output)
+        })
+    } else {
+        compare(&args.logs[0], &args.logs[1]).map(|divergence| {
+            let output = match args.json {
+                true => divergence_json(divergence.as_ref()),
+                false => divergence_text(divergence.as_ref()),
+            };
+            (divergence.is_some(), output)
+        })
+    };
+
+    match result {
+        Ok((diverged, output)) => {
+            print!("{output}");
+            ExitCode::from(u8::from(diverged))
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// The records of one label at the first divergent frame.
+struct LabelDivergence {
+    label: String,
+    left: Option<Record>,
+    right: Option<Record>,
+    /// Present when both sides recorded a tree.
+    diff: Option<HashDiff>,
+}
+
+struct Divergence {
+    frame: u64,
+    labels: Vec<LabelDivergence>,
+}
+
+/// Iterates over the records of a log one frame at a time.
+struct Frames {
+    records: Peekable<RecordReader<io::BufReader<std::fs::File>>>,
+}
+
+impl Frames {
+    fn open(path: &PathBuf) -> io::Result<Self> {
+        let reader = RecordReader::open(path)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+        Ok(Frames {
+            records: reader.peekable(),
+        })
+    }
+
+    /// Read the records of the next frame.
+    fn next_frame(&mut self) -> io::Result<Option<(u64, Vec<Record>)>> {
+        let Some(first) = self.records.next().transpose()? else {
+            return Ok(None);
+        };
+        let frame = first.frame;
+        let mut records = vec![first];
+        while let Some(Ok(record)) = self.records.peek() {
+            if record.frame != frame {
+                break;
+            }
+            records.push(self.records.next().unwrap()?);
+        }
+        // Surface a read error from the peek.
+        if let Some(Err(_)) = self.records.peek() {
+            self.records.next().unwrap()?;
+        }
+        Ok(Some((frame, records)))
+    }
+}
+
+/// Find the first frame at which the logs at `left` and `right` differ.
+fn compare(left: &PathBuf, right: &PathBuf) -> io::Result<Option<Divergence>> {
+    let mut left = Frames::open(left)?;
+    let mut right = Frames::open(right)?;
+    let mut l = left.next_frame()?;
+    let mut r = right.next_frame()?;
+
+    loop {
+        let (frame, left_records, right_records) = match (l.take(), r.take()) {
+            (None, None) => return Ok(None),
+            (Some((frame, records)), None) => (frame, records, Vec::new()),
+            (None, Some((frame, records))) => (frame, Vec::new(), records),
+            (Some((lf, lr)), Some((rf, rr))) => {
+                if lf < rf {
+                    r = Some((rf, rr));
+                    (lf, lr, Vec::new())
+                } else if rf < lf {
+                    l = Some((lf, lr));
+                    (rf, Vec::new(), rr)
+                } else {
+                    (lf, lr, rr)
+                }
+            }
+        };
+
+        let labels = compare_frame(left_records, right_records);
+        if !labels.is_empty() {
+            return Ok(Some(Divergence { frame, labels }));
+        }
+        if l.is_none() {
+            l = left.next_frame()?;
+        }
+        if r.is_none() {
+            r = right.next_frame()?;
+        }
+    }
+}
+
+fn compare_frame(left: Vec<Record>, mut right: Vec<Record>) -> Vec<LabelDivergence> {
+    let mut labels = Vec::new();
+    for left in left {
+        let right = right
+            .iter()
+            .position(|r| r.label == left.label)
+            .map(|i| right.remove(i));
+        if right.as_ref().is_some_and(|right| right.hash == left.hash) {
+            continue;
+        }
+        let diff = match (&left.tree, right.as_ref().and_then(|r| r.tree.as_ref())) {
+            (Some(l), Some(r)) => Some(l.diff(r)),
+            _ => None,
+        };
+        labels.push(LabelDivergence {
+            label: left.label.clone(),
+            left: Some(left),
+            right,
+            diff,
+        });
+    }
+    labels.extend(right.into_iter().map(|right| LabelDivergence {
+        label: right.label.clone(),
+        left: None,
+        right: Some(right),
+        diff: None,
+    }));
+    labels
+}
+
+/// The first run that diverges from the reference, and how.
+struct BisectReport {
+    index: usize,
+    divergence: Divergence,
+}
+
+fn bisect(logs: &[PathBuf]) -> io::Result<Option<BisectReport>> {
+    let reference = &logs[0];
+    let last = logs.len() - 1;
+    let Some(divergence) = compare(reference, &logs[last])? else {
+        return Ok(None);
+    };
+
+    // `good` matches the reference, `bad` diverges from it.
+    let (mut good, mut bad, mut bad_divergence) = (0, last, divergence);
+    while bad - good > 1 {
+        let mid = good + (bad - good) / 2;
+        match compare(reference, &logs[mid])? {
+            Some(divergence) => (bad, bad_divergence) = (mid, divergence),
+            None => good = mid,
+        }
+    }
+    Ok(Some(BisectReport {
+        index: bad,
+        divergence: bad_divergence,
+    }))
+}
+
+fn hex(hash: u64) -> String {
+    format!("{hash:#018x}")
+}
+
+fn divergence_text(divergence: Option<&Divergence>) -> String {
+    let Some(divergence) = divergence else {
+        return "logs match\n".to_string();
+    };
+
+    let mut out = format!("first divergence at frame {}\n", divergence.frame);
+    for label in &divergence.labels {
+        let side = |record: &Option<Record>| match record {
+            Some(record) => hex(record.hash),
+            None => "(missing)".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "  {}: {} -> {}",
+            label.label,
+            side(&label.left),
+            side(&label.right)
+        );
+        if let Some(diff) = &label.diff {
+            for difference in &diff.differences {
+                let _ = writeln!(out, "    {difference}");
+            }
+        }
+    }
+    out
+}
+
+fn bisect_text(logs: &[PathBuf], report: Option<&BisectReport>) -> String {
+    let Some(report) = report else {
+        return "all runs match the reference\n".to_string();
+    };
+    format!(
+        "first divergent run: {} (run {} of {})\n{}",
+        logs[report.index].display(),
+        report.index,
+        logs.len() - 1,
+        divergence_text(Some(&report.divergence))
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Hashes are written as hex strings, since JSON numbers cannot hold every `u64`.
+fn json_hash(hash: Option<u64>) -> String {
+    hash.map_or_else(|| "null".to_string(), |hash| json_string(&hex(hash)))
+}
+
+fn divergence_json(divergence: Option<&Divergence>) -> String {
+    format!("{}\n", divergence_json_value(divergence))
+}
+
+fn divergence_json_value(divergence: Option<&Divergence>) -> String {
+    let Some(divergence) = divergence else {
+        return r#"{"diverged":false}"#.to_string();
+    };
+
+    let labels: Vec<String> = divergence
+        .labels
+        .iter()
+        .map(|label| {
+            let paths = match &label.diff {
+                Some(diff) => {
+                    let paths: Vec<String> = diff
+                        .differences
+                        .iter()
+                        .map(|d| {
+                            format!(
+                                r#"{{"path":{},"left":{},"right":{}}}"#,
+                                json_string(&d.path),
+                                json_hash(d.left.as_ref().map(|n| n.hash)),
+                                json_hash(d.right.as_ref().map(|n| n.hash)),
+                            )
+                        })
+                        .collect();
+                    format!("[{}]", paths.join(","))
+                }
+                None => "null".to_string(),
+            };
+            format!(
+                r#"{{"label":{},"left":{},"right":{},"paths":{}}}"#,
+                json_string(&label.label),
+                json_hash(label.left.as_ref().map(|r| r.hash)),
+                json_hash(label.right.as_ref().map(|r| r.hash)),
+                paths,
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{"diverged":true,"frame":{},"labels":[{}]}}"#,
+        divergence.frame,
+        labels.join(",")
+    )
+}
+
+fn bisect_json(logs: &[PathBuf], report: Option<&BisectReport>) -> String {
+    let Some(report) = report else {
+        return "{\"diverged\":false}\n".to_string();
+    };
+    format!(
+        "{{\"diverged\":true,\"run\":{},\"log\":{},\"divergence\":{}}}\n",
+        report.index,
+        json_string(&logs[report.index].to_string_lossy()),
+        divergence_json_value(Some(&report.divergence))
+    )
+}
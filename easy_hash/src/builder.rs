@@ -0,0 +1,127 @@
+//! Hashing values together without declaring a type for them.
+//!
+//! A [`HashBuilder`] puts a hash together the way derived impls do: a salt, then
+//! the hash of each field in order. It is also the building block of hand-written
+//! impls for foreign types:
+//!
+//! ```
+//! use easy_hash::{EasyHash, HashBuilder, type_salt};
+//!
+//! struct Span {
+//!     start: u32,
+//!     len: u32,
+//! }
+//!
+//! impl EasyHash for Span {
+//!     const TYPE_SALT: u32 = type_salt::<Self>();
+//!     fn ehash(&self) -> u64 {
+//!         HashBuilder::new()
+//!             .salt(Self::TYPE_SALT)
+//!             .field(&self.start)
+//!             .field(&self.len)
+//!             .finish()
+//!     }
+//! }
+//!
+//! // Derived impls hash the same way.
+//! #[derive(EasyHash)]
+//! struct DerivedSpan {
+//!     start: u32,
+//!     len: u32,
+//! }
+//!
+//! let derived = DerivedSpan { start: 3, len: 4 };
+//! let built = HashBuilder::new()
+//!     .salt(DerivedSpan::TYPE_SALT)
+//!     .field(&3u32)
+//!     .field(&4u32)
+//!     .finish();
+//! assert_eq!(derived.ehash(), built);
+//! ```
+//!
+//! The [`ehash!`](crate::ehash) macro hashes a few values without a salt.
+
+use core::marker::PhantomData;
+
+use fletcher::Fletcher64;
+
+use crate::{EasyHash, HashFormat, u64_to_u32_slice, v1};
+
+/// Builds a hash from a salt and a sequence of fields, in the format `F`.
+///
+/// `HashBuilder::new()` hashes in v1, like `ehash()`; other formats start from
+/// `HashBuilder::<F>::default()`.
+#[must_use]
+pub struct HashBuilder<F: HashFormat = v1::V1> {
+    checksum: Fletcher64,
+    format: PhantomData<F>,
+}
+
+impl HashBuilder {
+    pub fn new() -> Self {
+        HashBuilder::default()
+    }
+}
+
+impl<F: HashFormat> Default for HashBuilder<F> {
+    fn default() -> Self {
+        HashBuilder {
+            checksum: Fletcher64::new(),
+            format: PhantomData,
+        }
+    }
+}
+
+impl<F: HashFormat> HashBuilder<F> {
+    /// Add a 32-bit salt, hashed as a single word in every format.
+    pub fn salt(mut self, salt: u32) -> Self {
+        self.checksum.update(&[salt]);
+        self
+    }
+
+    /// Add the salt of `T` the way derived impls of `T` do, which depends on the
+    /// format.
+    pub fn salt_of<T: EasyHash + ?Sized>(mut self) -> Self {
+        F::update_salt(&mut self.checksum, T::TYPE_SALT, T::TYPE_SALT64);
+        self
+    }
+
+    /// Add the hash of `value` in the format `F`.
+    pub fn field<T: EasyHash + ?Sized>(mut self, value: &T) -> Self {
+        self.checksum
+            .update(u64_to_u32_slice(&[value.ehash_in::<F>()]));
+        self
+    }
+
+    /// Add raw words, e.g. the bits of floats, without hashing them first.
+    pub fn words(mut self, words: &[u32]) -> Self {
+        self.checksum.update(words);
+        self
+    }
+
+    pub fn finish(self) -> u64 {
+        F::finish(self.checksum.value())
+    }
+}
+
+/// Hash any number of values together, in order, like the fields of a struct
+/// without its salt.
+///
+/// `ehash!(a, b)` is the same as `HashBuilder::new().field(&a).field(&b).finish()`.
+/// The values are only borrowed.
+///
+/// ```
+/// use easy_hash::ehash;
+///
+/// let (tick, seed, name) = (7u64, 42u32, "ada");
+/// let key = ehash!(tick, seed, name);
+/// assert_ne!(key, ehash!(seed, tick, name));
+/// ```
+#[macro_export]
+macro_rules! ehash {
+    ($($value:expr),+ $(,)?) => {
+        $crate::HashBuilder::new()
+            $(.field(&$value))+
+            .finish()
+    };
+}
@@ -1,17 +1,66 @@
-use crate::{EasyHash, type_salt};
+use crate::simd::Sums;
+use crate::{EasyHash, HashFormat, type_salt, v1};
 use bytemuck::{Pod, cast_slice, pod_align_to};
 use fletcher::Fletcher64;
 
-/// Update the checksum with at most 4 bytes of `data`,
-/// padding with zeroes if needed.
-fn update_unaligned<T: Pod>(checksum: &mut Fletcher64, data: &[T]) {
-    if !data.is_empty() {
-        let bytes: &[u8] = cast_slice::<T, u8>(data);
-        debug_assert!(bytes.len() < 4);
-        let mut buf = [0u8; 4];
-        buf[..bytes.len()].copy_from_slice(bytes);
-        checksum.update(&[u32::from_le_bytes(buf)]);
+/// The sums of the bytes of `data` packed little-endian into `u32`s, padding the
+/// last word with zeroes.
+///
+/// This is at most 3 bytes unless `size_of::<T>()` is not a multiple of 4, or the
+/// format hashes `Pod` data independently of its address.
+pub(crate) fn unaligned_sums<T: Pod>(data: &[T]) -> Sums {
+    let bytes: &[u8] = cast_slice::<T, u8>(data);
+    let mut words = [0u32; 1024];
+    let mut sums = Sums::EMPTY;
+    for chunk in bytes.chunks(4 * words.len()) {
+        let len = chunk.len().div_ceil(4);
+        for (word, bytes) in words.iter_mut().zip(chunk.chunks(4)) {
+            let mut buf = [0u8; 4];
+            buf[..bytes.len()].copy_from_slice(bytes);
+            *word = u32::from_le_bytes(buf);
+        }
+        sums = sums.then(Sums::of_words(&words[..len]));
     }
+    sums
+}
+
+/// Hash the byte representation of `data`, salted with `salt`.
+///
+/// This is the path used for arrays and slices of [`Pod`] values, and by
+/// types deriving `EasyHash` with `#[easy_hash(bytes)]`.
+pub fn ehash_pod_slice<T: Pod>(salt: u32, data: &[T]) -> u64 {
+    // v1 only uses the 32-bit salt.
+    ehash_pod_slice_in::<v1::V1, T>(salt, 0, data)
+}
+
+/// Hash the byte representation of `data` in the format `F`, salted with `salt` or
+/// `salt64` depending on the format.
+///
+/// In address-independent formats the bytes are packed little-endian from the
+/// start of the slice, so the hash does not depend on its alignment.
+pub fn ehash_pod_slice_in<F: HashFormat, T: Pod>(salt: u32, salt64: u64, data: &[T]) -> u64 {
+    let mut checksum = Fletcher64::new();
+    F::update_salt(&mut checksum, salt, salt64);
+    F::finish(
+        Sums::of_checksum(&checksum, 0)
+            .then(pod_sums::<F, T>(data))
+            .value(),
+    )
+}
+
+/// The sums of the bytes of `data` in the format `F`, which are checksummed with
+/// [`simd`](crate::simd) rather than added to a `Fletcher64` word by word.
+fn pod_sums<F: HashFormat, T: Pod>(data: &[T]) -> Sums {
+    if F::ADDRESS_INDEPENDENT {
+        return unaligned_sums(data);
+    }
+
+    // split into head / aligned body / tail
+    let (head, body_u32, tail) = pod_align_to::<T, u32>(data);
+
+    unaligned_sums(head)
+        .then(Sums::of_words(body_u32))
+        .then(unaligned_sums(tail))
 }
 
 impl<T, const N: usize> EasyHash for [T; N]
@@ -21,17 +70,11 @@ where
     const TYPE_SALT: u32 = type_salt::<[T; N]>();
 
     fn ehash(&self) -> u64 {
-        let mut checksum = Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
-
-        // split into head / aligned body / tail
-        let (head, body_u32, tail) = pod_align_to::<T, u32>(self);
-
-        update_unaligned(&mut checksum, head);
-        checksum.update(body_u32);
-        update_unaligned(&mut checksum, tail);
+        self.ehash_in::<v1::V1>()
+    }
 
-        checksum.value()
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        ehash_pod_slice_in::<F, T>(Self::TYPE_SALT, Self::TYPE_SALT64, self)
     }
 }
 
@@ -42,15 +85,18 @@ where
     const TYPE_SALT: u32 = type_salt::<&[T]>();
 
     fn ehash(&self) -> u64 {
-        let mut checksum = Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
-
-        let (head, body_u32, tail) = pod_align_to::<T, u32>(self);
-
-        update_unaligned(&mut checksum, head);
-        checksum.update(body_u32);
-        update_unaligned(&mut checksum, tail);
+        self.ehash_in::<v1::V1>()
+    }
 
-        checksum.value()
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        // Unlike arrays, slices of different lengths have the same type.
+        let mut checksum = Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+        F::finish(
+            Sums::of_checksum(&checksum, 0)
+                .then(pod_sums::<F, T>(self))
+                .value(),
+        )
     }
 }
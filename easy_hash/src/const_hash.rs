@@ -0,0 +1,321 @@
+//! Hashing in constant expressions.
+//!
+//! [`const_ehash!`](crate::const_ehash) computes the `ehash()` of integers, `bool`,
+//! `&str`, arrays of integers and tuples of those at compile time, e.g. for IDs
+//! derived from literals:
+//!
+//! ```
+//! use easy_hash::{EasyHash, const_ehash};
+//!
+//! const SPAWN: u64 = const_ehash!(("spawn", 3u32));
+//! assert_eq!(SPAWN, ("spawn", 3u32).ehash());
+//! ```
+//!
+//! Integer literals need a suffix, since the hash depends on their type.
+//!
+//! The hash of an array of `u8`, `i8`, `u16` or `i16` is that of an array starting
+//! at a 4-byte aligned address. At run time, such arrays hash according to their
+//! address in the v1 format.
+
+use crate::type_salt;
+
+/// Fletcher's checksum over `u32`s, equal to [`fletcher::Fletcher64`] but usable
+/// in `const fn`s.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstFletcher64 {
+    a: u64,
+    b: u64,
+    /// Words added since the last reduction of the current `update`.
+    pending: usize,
+}
+
+/// The number of words `Fletcher64` adds up between reductions.
+const CHUNK: usize = 92680;
+
+impl ConstFletcher64 {
+    pub const fn new() -> Self {
+        ConstFletcher64 {
+            a: 0,
+            b: 0,
+            pending: 0,
+        }
+    }
+
+    pub const fn update(&mut self, data: &[u32]) {
+        let mut i = 0;
+        while i < data.len() {
+            self.push(data[i]);
+            i += 1;
+        }
+        self.end_update();
+    }
+
+    pub const fn value(&self) -> u64 {
+        (self.b << 32) | self.a
+    }
+
+    /// Add a word to the current `update`.
+    const fn push(&mut self, word: u32) {
+        self.a += word as u64;
+        self.b += self.a;
+        self.pending += 1;
+        if self.pending == CHUNK {
+            self.reduce();
+            self.pending = 0;
+        }
+    }
+
+    /// End the current `update`, reducing after the last (partial) chunk and once
+    /// more.
+    const fn end_update(&mut self) {
+        if self.pending > 0 {
+            self.reduce();
+            self.pending = 0;
+        }
+        self.reduce();
+    }
+
+    const fn reduce(&mut self) {
+        self.a = reduce(self.a);
+        self.b = reduce(self.b);
+    }
+}
+
+/// Fold the high half of a sum into the low half, as `Fletcher64` does, with
+/// `2^32 - 1` written as 0.
+const fn reduce(sum: u64) -> u64 {
+    let reduced = (sum & 0xffff_ffff) + (sum >> 32);
+    if reduced == 0xffff_ffff { 0 } else { reduced }
+}
+
+impl Default for ConstFletcher64 {
+    fn default() -> Self {
+        ConstFletcher64::new()
+    }
+}
+
+/// [`fletcher::calc_fletcher64`] as a `const fn`.
+pub const fn calc_fletcher64(data: &[u32]) -> u64 {
+    let mut checksum = ConstFletcher64::new();
+    checksum.update(data);
+    checksum.value()
+}
+
+/// A value hashed by [`const_ehash!`](crate::const_ehash).
+///
+/// `ConstHash(x).ehash()` is a `const fn` equal to `x.ehash()` for each supported
+/// type of `x`.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstHash<T>(pub T);
+
+impl ConstHash<bool> {
+    pub const fn ehash(&self) -> u64 {
+        calc_fletcher64(&[type_salt::<bool>(), self.0 as u32])
+    }
+}
+
+/// Integers of at most 32 bits, hashed as one word with an extra salt for the
+/// all-ones value.
+macro_rules! const_hash_narrow_impl {
+    ($($T:ty => $U:ty),+ $(,)?) => {
+        $(
+            impl ConstHash<$T> {
+                pub const fn ehash(&self) -> u64 {
+                    const SALT: u32 = type_salt::<$T>();
+                    let word = self.0 as u32;
+                    if self.0 as $U == <$U>::MAX {
+                        if size_of::<$T>() == 4 {
+                            calc_fletcher64(&[SALT, word, SALT])
+                        } else {
+                            calc_fletcher64(&[SALT, word | SALT])
+                        }
+                    } else {
+                        calc_fletcher64(&[SALT, word])
+                    }
+                }
+            }
+        )+
+    };
+}
+
+const_hash_narrow_impl!(u8 => u8, u16 => u16, u32 => u32, i8 => u8, i16 => u16, i32 => u32);
+
+/// 64-bit integers, hashed as two words with an extra salt for the all-ones value.
+macro_rules! const_hash_wide_impl {
+    ($($T:ty),+ $(,)?) => {
+        $(
+            impl ConstHash<$T> {
+                pub const fn ehash(&self) -> u64 {
+                    const SALT: u32 = type_salt::<$T>();
+                    let value = self.0 as u64;
+                    let mut checksum = ConstFletcher64::new();
+                    checksum.update(&[SALT]);
+                    checksum.update(&[(value >> 32) as u32, value as u32]);
+                    if value == u64::MAX {
+                        checksum.update(&[SALT]);
+                    }
+                    checksum.value()
+                }
+            }
+        )+
+    };
+}
+
+const_hash_wide_impl!(u64, usize, i64, isize);
+
+impl ConstHash<&str> {
+    pub const fn ehash(&self) -> u64 {
+        let mut checksum = ConstFletcher64::new();
+        checksum.update(&[type_salt::<&str>()]);
+        let bytes = self.0.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let mut word = [0u8; 4];
+            let mut j = 0;
+            while j < 4 && i + j < bytes.len() {
+                word[j] = bytes[i + j];
+                j += 1;
+            }
+            checksum.update(&[u32::from_le_bytes(word)]);
+            i += 4;
+        }
+        checksum.value()
+    }
+}
+
+/// Packs the bytes of an array into words like `ehash_pod_slice` does for a
+/// 4-byte aligned array: whole words in one `update`, then the remaining bytes.
+struct PodWords {
+    checksum: ConstFletcher64,
+    word: [u8; 4],
+    filled: usize,
+}
+
+impl PodWords {
+    const fn new(salt: u32) -> Self {
+        let mut checksum = ConstFletcher64::new();
+        checksum.update(&[salt]);
+        PodWords {
+            checksum,
+            word: [0; 4],
+            filled: 0,
+        }
+    }
+
+    const fn push_bytes(&mut self, bytes: &[u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.word[self.filled] = bytes[i];
+            self.filled += 1;
+            if self.filled == 4 {
+                self.checksum.push(u32::from_ne_bytes(self.word));
+                self.filled = 0;
+            }
+            i += 1;
+        }
+    }
+
+    const fn value(mut self) -> u64 {
+        self.checksum.end_update();
+        if self.filled > 0 {
+            let mut tail = [0u8; 4];
+            let mut i = 0;
+            while i < self.filled {
+                tail[i] = self.word[i];
+                i += 1;
+            }
+            self.checksum.update(&[u32::from_le_bytes(tail)]);
+        }
+        self.checksum.value()
+    }
+}
+
+macro_rules! const_hash_array_impl {
+    ($($T:ty),+ $(,)?) => {
+        $(
+            impl<const N: usize> ConstHash<[$T; N]> {
+                pub const fn ehash(&self) -> u64 {
+                    let mut words = PodWords::new(type_salt::<[$T; N]>());
+                    let mut i = 0;
+                    while i < N {
+                        words.push_bytes(&self.0[i].to_ne_bytes());
+                        i += 1;
+                    }
+                    words.value()
+                }
+            }
+        )+
+    };
+}
+
+const_hash_array_impl!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// The salt of the type of `value`. Used by [`const_ehash!`](crate::const_ehash)
+/// for tuples.
+#[doc(hidden)]
+pub const fn salt_of<T>(_value: &T) -> u32 {
+    type_salt::<T>()
+}
+
+/// The hash of a tuple with the given salt and element hashes.
+#[doc(hidden)]
+pub const fn ehash_tuple(salt: u32, hashes: &[u64]) -> u64 {
+    let mut checksum = ConstFletcher64::new();
+    checksum.update(&[salt]);
+    // Element hashes are hashed as native-endian halves, like `u64_to_u32_slice`.
+    let mut i = 0;
+    while i < hashes.len() {
+        let bytes = hashes[i].to_ne_bytes();
+        checksum.push(u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        checksum.push(u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]));
+        i += 1;
+    }
+    checksum.end_update();
+    checksum.value()
+}
+
+/// The `ehash()` of a value, usable in constants.
+///
+/// Supports integers, `bool`, `&str`, arrays of integers, and tuple expressions
+/// of those.
+///
+/// ```
+/// use easy_hash::{EasyHash, const_ehash};
+///
+/// const IDS: [u64; 3] = [
+///     const_ehash!("spawn"),
+///     const_ehash!([1u16, 2, 3]),
+///     const_ehash!((true, -1i64)),
+/// ];
+/// assert_eq!(IDS[0], "spawn".ehash());
+/// assert_eq!(IDS[2], (true, -1i64).ehash());
+/// ```
+#[macro_export]
+macro_rules! const_ehash {
+    (($e:expr,)) => {{
+        let tuple = ($e,);
+        $crate::__const_ehash_tuple!(tuple [] [$e] [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14])
+    }};
+    (($e:expr, $($rest:expr),+ $(,)?)) => {{
+        let tuple = ($e, $($rest),+);
+        $crate::__const_ehash_tuple!(tuple [] [$e, $($rest),+] [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14])
+    }};
+    ($e:expr) => {
+        $crate::const_hash::ConstHash($e).ehash()
+    };
+}
+
+/// Hash the elements of `$tuple`, taking one index per element expression.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_ehash_tuple {
+    ($tuple:ident [$($index:tt)*] [] [$($unused:tt)*]) => {
+        $crate::const_hash::ehash_tuple(
+            $crate::const_hash::salt_of(&$tuple),
+            &[$($crate::const_hash::ConstHash($tuple.$index).ehash()),*],
+        )
+    };
+    ($tuple:ident [$($index:tt)*] [$_e:expr $(, $rest:expr)*] [$next:tt $($more:tt)*]) => {
+        $crate::__const_ehash_tuple!($tuple [$($index)* $next] [$($rest),*] [$($more)*])
+    };
+}
@@ -0,0 +1,179 @@
+//! Checking that a simulation is deterministic by running it twice and comparing
+//! the hash of its state every frame.
+//!
+//! The two instances run on their own threads, and the second one keeps some
+//! extra allocations alive so that its state ends up at different addresses.
+//! They advance in lockstep, so a divergence stops both at the same frame.
+
+use std::fmt;
+use std::panic;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+use crate::EasyHash;
+
+/// The first frame at which the two instances of a simulation hashed differently.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// 0 for the state returned by `setup`, `n` for the state after `n` steps.
+    pub frame: u64,
+    pub left: u64,
+    pub right: u64,
+    /// Where the states differ.
+    #[cfg(feature = "tree")]
+    pub diff: crate::tree::HashDiff,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "state diverged at frame {}: {:#018x} != {:#018x}",
+            self.frame, self.left, self.right
+        )?;
+        #[cfg(feature = "tree")]
+        write!(f, "\n{}", self.diff)?;
+        Ok(())
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Run two instances of a simulation for `frames` steps each and compare the
+/// `ehash` of their states after `setup` and after every step.
+///
+/// ```
+/// use easy_hash::EasyHash;
+///
+/// #[derive(EasyHash)]
+/// struct World {
+///     tick: u64,
+///     x: i64,
+/// }
+///
+/// let result = easy_hash::determinism::check(
+///     || World { tick: 0, x: 1 },
+///     |world| {
+///         world.tick += 1;
+///         world.x = world.x * 3 % 1_000_003;
+///     },
+///     1_000,
+/// );
+/// assert!(result.is_ok());
+/// ```
+pub fn check<S, Setup, Step>(setup: Setup, step: Step, frames: u64) -> Result<(), Divergence>
+where
+    S: EasyHash,
+    Setup: Fn() -> S + Sync,
+    Step: Fn(&mut S) + Sync,
+{
+    thread::scope(|scope| {
+        let (left_reports, left_rx) = channel();
+        let (left_commands, left_cmd_rx) = channel();
+        let (right_reports, right_rx) = channel();
+        let (right_commands, right_cmd_rx) = channel();
+
+        let (setup, step) = (&setup, &step);
+        let left_instance =
+            scope.spawn(move || run_instance(setup, step, frames, 0, left_reports, left_cmd_rx));
+        let right_instance =
+            scope.spawn(move || run_instance(setup, step, frames, 64, right_reports, right_cmd_rx));
+
+        for frame in 0..=frames {
+            let (Ok(Report::Hash(left)), Ok(Report::Hash(right))) =
+                (left_rx.recv(), right_rx.recv())
+            else {
+                // An instance only hangs up by panicking; pass its panic on.
+                drop((left_commands, right_commands));
+                for instance in [left_instance, right_instance] {
+                    if let Err(payload) = instance.join() {
+                        panic::resume_unwind(payload);
+                    }
+                }
+                unreachable!("a simulation instance stopped without panicking");
+            };
+
+            if left != right {
+                let _ = left_commands.send(Command::Finish);
+                let _ = right_commands.send(Command::Finish);
+                return Err(Divergence {
+                    frame,
+                    left,
+                    right,
+                    #[cfg(feature = "tree")]
+                    diff: match (left_rx.recv(), right_rx.recv()) {
+                        (Ok(Report::Tree(left)), Ok(Report::Tree(right))) => left.diff(&right),
+                        _ => Default::default(),
+                    },
+                });
+            }
+
+            let _ = left_commands.send(Command::Continue);
+            let _ = right_commands.send(Command::Continue);
+        }
+        Ok(())
+    })
+}
+
+/// Like [`check`], but panics with the divergence, for use in tests.
+#[track_caller]
+pub fn assert_deterministic<S, Setup, Step>(setup: Setup, step: Step, frames: u64)
+where
+    S: EasyHash,
+    Setup: Fn() -> S + Sync,
+    Step: Fn(&mut S) + Sync,
+{
+    if let Err(divergence) = check(setup, step, frames) {
+        panic!("{divergence}");
+    }
+}
+
+enum Report {
+    Hash(u64),
+    #[cfg(feature = "tree")]
+    Tree(crate::tree::HashTree),
+}
+
+enum Command {
+    Continue,
+    /// Stop, after sending the hash tree of the current state if available.
+    Finish,
+}
+
+fn run_instance<S, Setup, Step>(
+    setup: &Setup,
+    step: &Step,
+    frames: u64,
+    padding: usize,
+    reports: Sender<Report>,
+    commands: Receiver<Command>,
+) where
+    S: EasyHash,
+    Setup: Fn() -> S,
+    Step: Fn(&mut S),
+{
+    // Differently sized allocations that live as long as the state, so that the two
+    // instances allocate their states at different addresses.
+    let _padding: Vec<Box<[u8]>> = (0..padding)
+        .map(|i| vec![0u8; 8 + 24 * i].into_boxed_slice())
+        .collect();
+
+    let mut state = setup();
+    for frame in 0..=frames {
+        if frame > 0 {
+            step(&mut state);
+        }
+        if reports.send(Report::Hash(state.ehash())).is_err() {
+            return;
+        }
+        match commands.recv() {
+            Ok(Command::Continue) => {}
+            Ok(Command::Finish) => {
+                #[cfg(feature = "tree")]
+                let _ = reports.send(Report::Tree(state.ehash_tree()));
+                return;
+            }
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! The hash formats that values can be hashed in.
+//!
+//! The format decides the details of how hashes are put together, so that they can
+//! be improved without changing the hashes that are already stored. A format
+//! stays bit-for-bit reproducible once released; improvements go into a new one.
+//! [`EasyHash::ehash`] always uses [`v1`], and [`EasyHash::ehash_in`] takes the
+//! format as a type parameter:
+//!
+//! ```
+//! use easy_hash::EasyHash;
+//! use easy_hash::v2::V2;
+//!
+//! #[derive(EasyHash)]
+//! struct Player {
+//!     name: &'static str,
+//!     score: u32,
+//! }
+//!
+//! let player = Player { name: "ada", score: 3 };
+//! assert_eq!(player.ehash(), easy_hash::v1::ehash(&player));
+//! assert_eq!(player.ehash_in::<V2>(), easy_hash::v2::ehash(&player));
+//! ```
+//!
+//! [`v1`]: crate::v1
+//! [`EasyHash::ehash`]: crate::EasyHash::ehash
+//! [`EasyHash::ehash_in`]: crate::EasyHash::ehash_in
+
+use fletcher::Fletcher64;
+
+/// A version of the hash format. Implemented by [`V1`](crate::v1::V1) and
+/// [`V2`](crate::v2::V2).
+pub trait HashFormat: 'static {
+    /// The number of the format.
+    const VERSION: u32;
+
+    /// Whether sequences such as `Vec`, slices and strings hash their length before
+    /// their elements, so that e.g. nested vectors split differently hash differently.
+    const LENGTH_PREFIX: bool;
+
+    /// Whether the bytes of `Pod` slices are hashed independently of where the
+    /// slice is in memory.
+    const ADDRESS_INDEPENDENT: bool;
+
+    /// Whether values are salted with [`TYPE_SALT64`](crate::EasyHash::TYPE_SALT64)
+    /// rather than the 32-bit `TYPE_SALT`.
+    const WIDE_SALTS: bool;
+
+    /// Applied to the checksum of every value to get its hash.
+    ///
+    /// Types that only implement `ehash` are hashed in every format by applying
+    /// this to their `ehash`.
+    fn finish(checksum: u64) -> u64;
+
+    /// Feed the length of a sequence into `checksum`, if the format does that.
+    #[inline]
+    fn update_len(checksum: &mut Fletcher64, len: usize) {
+        if Self::LENGTH_PREFIX {
+            checksum.update(&crate::split_u64(len as u64));
+        }
+    }
+
+    /// Feed the type salt of a value into `checksum`: `salt64` in formats with wide
+    /// salts, `salt` otherwise.
+    #[inline]
+    fn update_salt(checksum: &mut Fletcher64, salt: u32, salt64: u64) {
+        if Self::WIDE_SALTS {
+            checksum.update(&crate::split_u64(salt64));
+        } else {
+            checksum.update(&[salt]);
+        }
+    }
+}
@@ -1,45 +1,64 @@
-use crate::{EasyHash, type_salt};
+use crate::{EasyHash, EasyHashQuantized, HashBuilder, Quantization, type_salt};
 
 use godot::builtin::Vector3;
 
 impl EasyHash for Vector3 {
     const TYPE_SALT: u32 = type_salt::<Self>();
     fn ehash(&self) -> u64 {
-        let mut checksum = fletcher::Fletcher64::new();
-        checksum.update(&[
-            Self::TYPE_SALT,
-            self.x.to_bits(),
-            self.y.to_bits(),
-            self.z.to_bits(),
-        ]);
-        checksum.value()
+        HashBuilder::new()
+            .salt(Self::TYPE_SALT)
+            .words(&[self.x.to_bits(), self.y.to_bits(), self.z.to_bits()])
+            .finish()
     }
 }
 
 impl EasyHash for godot::builtin::Vector2 {
     const TYPE_SALT: u32 = type_salt::<Self>();
     fn ehash(&self) -> u64 {
-        let mut checksum = fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT, self.x.to_bits(), self.y.to_bits()]);
-
-        checksum.value()
+        HashBuilder::new()
+            .salt(Self::TYPE_SALT)
+            .words(&[self.x.to_bits(), self.y.to_bits()])
+            .finish()
     }
 }
 
 impl EasyHash for godot::builtin::Vector3i {
     const TYPE_SALT: u32 = type_salt::<Self>();
     fn ehash(&self) -> u64 {
-        let mut checksum = fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT, self.x as u32, self.y as u32, self.z as u32]);
-        checksum.value()
+        HashBuilder::new()
+            .salt(Self::TYPE_SALT)
+            .words(&[self.x as u32, self.y as u32, self.z as u32])
+            .finish()
     }
 }
 
 impl EasyHash for godot::builtin::Vector2i {
     const TYPE_SALT: u32 = type_salt::<Self>();
     fn ehash(&self) -> u64 {
-        let mut checksum = fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT, self.x as u32, self.y as u32]);
-        checksum.value()
+        HashBuilder::new()
+            .salt(Self::TYPE_SALT)
+            .words(&[self.x as u32, self.y as u32])
+            .finish()
+    }
+}
+
+impl EasyHashQuantized for Vector3 {
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        Vector3::new(
+            quantization.quantize_f32(self.x),
+            quantization.quantize_f32(self.y),
+            quantization.quantize_f32(self.z),
+        )
+        .ehash()
+    }
+}
+
+impl EasyHashQuantized for godot::builtin::Vector2 {
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        godot::builtin::Vector2::new(
+            quantization.quantize_f32(self.x),
+            quantization.quantize_f32(self.y),
+        )
+        .ehash()
     }
 }
@@ -1,7 +1,14 @@
-#![doc = include_str!("../../README.md")]
+#![cfg_attr(feature = "std", doc = include_str!("../../README.md"))]
 #![feature(const_type_name)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use const_fnv1a_hash::fnv1a_hash_str_32;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use const_fnv1a_hash::{fnv1a_hash_str_32, fnv1a_hash_str_64};
 
 #[cfg(feature = "godot")]
 pub mod godot;
@@ -9,8 +16,16 @@ pub mod godot;
 pub mod nalgebra;
 #[cfg(feature = "ordered_float")]
 pub mod ordered_float;
+#[cfg(feature = "rayon")]
+pub mod par;
 #[cfg(feature = "rapier")]
 pub mod rapier;
+#[cfg(feature = "record")]
+pub mod record;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "tree")]
+pub mod tree;
 #[cfg(feature = "bevy")]
 use bevy_ecs::prelude::Mut;
 #[cfg(feature = "bevy")]
@@ -19,32 +34,120 @@ where
     T: EasyHash,
 {
     const TYPE_SALT: u32 = type_salt::<&T>();
+    const SCHEMA_HASH: u64 = T::SCHEMA_HASH;
 
     fn ehash(&self) -> u64 {
         (**self).ehash()
     }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        (**self).ehash_in::<F>()
+    }
+
+    fn ehash_group(&self, group: &str) -> u64 {
+        (**self).ehash_group(group)
+    }
+
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        (**self).ehash_tree()
+    }
 }
 
+pub use bytemuck;
 pub use easy_hash_derive::*;
 pub use fletcher;
 
 use fletcher::*;
 
+pub mod builder;
 pub mod bytemuck_slices;
+pub mod const_hash;
+#[cfg(feature = "std")]
+pub mod determinism;
+pub mod format;
 pub mod primitives;
+pub mod quantize;
+pub mod schema;
+pub mod simd;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "alloc")]
+pub mod stability;
 pub mod std_once_cell;
 pub mod tuples;
 pub mod type_id;
 pub mod type_salt_generic;
+pub mod v1;
+pub mod v2;
+pub mod version;
+pub use builder::HashBuilder;
+pub use format::HashFormat;
+#[cfg(feature = "rayon")]
+pub use par::ParEasyHash;
+pub use quantize::{EasyHashQuantized, Quantization};
+#[cfg(feature = "tree")]
+pub use tree::diff;
 pub use type_salt_generic::*;
+pub use version::EasyHashVersioned;
+
+/// Hashed in place of the inner value's hash for `None`.
+pub(crate) const OPTION_NONE_VAL: u32 = 780526312;
 
 pub trait EasyHash {
     const TYPE_SALT: u32;
+
+    /// A 64-bit salt, used instead of `TYPE_SALT` by formats with
+    /// [`WIDE_SALTS`](HashFormat::WIDE_SALTS).
+    ///
+    /// Defaults to a hash of the type name.
+    const TYPE_SALT64: u64 = type_salt64::<Self>();
+
+    /// A fingerprint of the type's shape, which changes when fields or variants are
+    /// added, removed, renamed or retyped. See [`schema`].
+    ///
+    /// Defaults to a fingerprint of the type name.
+    const SCHEMA_HASH: u64 = schema::hash_str(core::any::type_name::<Self>());
+
     fn ehash(&self) -> u64;
+
+    /// Hash the value in the format `F`. `ehash()` is the same as
+    /// `ehash_in::<v1::V1>()`.
+    ///
+    /// Types that only implement `ehash` are hashed whole, by applying
+    /// [`HashFormat::finish`] to their `ehash`.
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        F::finish(self.ehash())
+    }
+
+    /// Hash only the fields tagged `#[easy_hash(group = "...")]` with `group`,
+    /// recursing into them.
+    ///
+    /// Types without field groups, such as primitives, are hashed whole.
+    fn ehash_group(&self, group: &str) -> u64 {
+        let _ = group;
+        self.ehash()
+    }
+
+    /// Hash the value like `ehash()`, recording the hash of every part of it.
+    ///
+    /// Types that are not broken down further become leaves of the tree.
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        tree::HashTree::new(
+            core::any::type_name::<Self>(),
+            Self::TYPE_SALT,
+            self.ehash(),
+        )
+    }
 }
 
 pub const fn type_salt<T>() -> u32 {
-    fnv1a_hash_str_32(std::any::type_name::<T>())
+    fnv1a_hash_str_32(core::any::type_name::<T>())
+}
+
+pub const fn type_salt64<T: ?Sized>() -> u64 {
+    fnv1a_hash_str_64(core::any::type_name::<T>())
 }
 
 #[inline]
@@ -72,14 +175,28 @@ where
     T: EasyHash,
 {
     const TYPE_SALT: u32 = type_salt::<&T>();
+    const SCHEMA_HASH: u64 = T::SCHEMA_HASH;
 
     fn ehash(&self) -> u64 {
         (**self).ehash()
     }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        (**self).ehash_in::<F>()
+    }
+
+    fn ehash_group(&self, group: &str) -> u64 {
+        (**self).ehash_group(group)
+    }
+
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        (**self).ehash_tree()
+    }
 }
 
-impl<T> EasyHash for std::marker::PhantomData<T> {
-    const TYPE_SALT: u32 = type_salt::<std::marker::PhantomData<T>>();
+impl<T> EasyHash for core::marker::PhantomData<T> {
+    const TYPE_SALT: u32 = type_salt::<core::marker::PhantomData<T>>();
 
     fn ehash(&self) -> u64 {
         // PhantomData has no runtime data, only type information
@@ -93,71 +210,214 @@ where
     T: EasyHash,
 {
     const TYPE_SALT: u32 = type_salt::<T>();
+    const SCHEMA_HASH: u64 = schema::compose("Option", [T::SCHEMA_HASH]);
 
     fn ehash(&self) -> u64 {
-        const NONE_VAL: u32 = 780526312;
+        self.ehash_in::<v1::V1>()
+    }
 
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = fletcher::Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
         if let Some(x) = self {
-            let parts = split_u64(x.ehash());
+            checksum.update(&split_u64(x.ehash_in::<F>()));
+        } else {
+            checksum.update(&[OPTION_NONE_VAL]);
+        }
+        F::finish(checksum.value())
+    }
+
+    fn ehash_group(&self, group: &str) -> u64 {
+        if let Some(x) = self {
+            let parts = split_u64(x.ehash_group(group));
             calc_fletcher64(&[Self::TYPE_SALT, parts[0], parts[1]])
         } else {
-            calc_fletcher64(&[Self::TYPE_SALT, NONE_VAL])
+            calc_fletcher64(&[Self::TYPE_SALT, OPTION_NONE_VAL])
+        }
+    }
+
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        let type_name = core::any::type_name::<Self>();
+        if let Some(x) = self {
+            let child = x.ehash_tree().with_name("Some");
+            let parts = split_u64(child.hash);
+            let hash = calc_fletcher64(&[Self::TYPE_SALT, parts[0], parts[1]]);
+            tree::HashTree::new(type_name, Self::TYPE_SALT, hash).with_children(vec![child])
+        } else {
+            tree::HashTree::new(type_name, Self::TYPE_SALT, self.ehash())
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> EasyHash for Vec<T>
 where
     T: EasyHash,
 {
     const TYPE_SALT: u32 = type_salt::<Vec<T>>();
+    const SCHEMA_HASH: u64 = schema::compose("Vec", [T::SCHEMA_HASH]);
 
     fn ehash(&self) -> u64 {
+        self.ehash_in::<v1::V1>()
+    }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = fletcher::Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+        let hashes: Vec<u64> = self.iter().map(|x| x.ehash_in::<F>()).collect();
+        checksum.update(u64_to_u32_slice(&hashes));
+        F::finish(checksum.value())
+    }
+
+    fn ehash_group(&self, group: &str) -> u64 {
         let mut checksum = fletcher::Fletcher64::new();
         checksum.update(&[Self::TYPE_SALT]);
-        let hashes: Vec<u64> = self.iter().map(|x| x.ehash()).collect();
+        let hashes: Vec<u64> = self.iter().map(|x| x.ehash_group(group)).collect();
         checksum.update(u64_to_u32_slice(&hashes));
         checksum.value()
     }
+
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        let children: Vec<tree::HashTree> = self
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x.ehash_tree().with_name(format!("[{i}]")))
+            .collect();
+        let hashes: Vec<u64> = children.iter().map(|child| child.hash).collect();
+
+        let mut checksum = fletcher::Fletcher64::new();
+        checksum.update(&[Self::TYPE_SALT]);
+        checksum.update(u64_to_u32_slice(&hashes));
+        tree::HashTree::new(
+            core::any::type_name::<Self>(),
+            Self::TYPE_SALT,
+            checksum.value(),
+        )
+        .with_children(children)
+    }
 }
 
-impl EasyHash for &str {
-    const TYPE_SALT: u32 = type_salt::<Self>();
+/// Entries are hashed in key order, each as the hash of its key followed by the
+/// hash of its value. There is no impl for `HashMap`, whose order varies between
+/// runs.
+#[cfg(feature = "alloc")]
+impl<K, V> EasyHash for BTreeMap<K, V>
+where
+    K: EasyHash,
+    V: EasyHash,
+{
+    const TYPE_SALT: u32 = type_salt::<BTreeMap<K, V>>();
+    const SCHEMA_HASH: u64 = schema::compose("BTreeMap", [K::SCHEMA_HASH, V::SCHEMA_HASH]);
+
     fn ehash(&self) -> u64 {
+        self.ehash_in::<v1::V1>()
+    }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = fletcher::Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+        let hashes: Vec<u64> = self
+            .iter()
+            .flat_map(|(k, v)| [k.ehash_in::<F>(), v.ehash_in::<F>()])
+            .collect();
+        checksum.update(u64_to_u32_slice(&hashes));
+        F::finish(checksum.value())
+    }
+
+    fn ehash_group(&self, group: &str) -> u64 {
         let mut checksum = fletcher::Fletcher64::new();
         checksum.update(&[Self::TYPE_SALT]);
-        let bytes = self.as_bytes();
-        let mut chunks = bytes.chunks_exact(4);
-        for chunk in &mut chunks {
-            checksum.update(&[u32::from_le_bytes(chunk.try_into().unwrap())]);
-        }
-        let remainder = chunks.remainder();
-        if !remainder.is_empty() {
-            let mut byte = [0u8; 4];
-            byte[..remainder.len()].copy_from_slice(remainder);
-            checksum.update(&[u32::from_le_bytes(byte)]);
-        }
+        let hashes: Vec<u64> = self
+            .iter()
+            .flat_map(|(k, v)| [k.ehash_group(group), v.ehash_group(group)])
+            .collect();
+        checksum.update(u64_to_u32_slice(&hashes));
         checksum.value()
     }
+
+    /// Each entry is a node with its key and value as children, hashed like the
+    /// tuple `(K, V)`.
+    #[cfg(feature = "tree")]
+    fn ehash_tree(&self) -> tree::HashTree {
+        let entry_salt = type_salt::<(K, V)>();
+        let mut hashes: Vec<u64> = Vec::with_capacity(2 * self.len());
+        let children: Vec<tree::HashTree> = self
+            .iter()
+            .enumerate()
+            .map(|(i, (k, v))| {
+                let key = k.ehash_tree().with_name("key");
+                let value = v.ehash_tree().with_name("value");
+                hashes.extend([key.hash, value.hash]);
+
+                let mut checksum = fletcher::Fletcher64::new();
+                checksum.update(&[entry_salt]);
+                checksum.update(u64_to_u32_slice(&[key.hash, value.hash]));
+                tree::HashTree::new(
+                    core::any::type_name::<(K, V)>(),
+                    entry_salt,
+                    checksum.value(),
+                )
+                .with_name(format!("[{i}]"))
+                .with_children(vec![key, value])
+            })
+            .collect();
+
+        let mut checksum = fletcher::Fletcher64::new();
+        checksum.update(&[Self::TYPE_SALT]);
+        checksum.update(u64_to_u32_slice(&hashes));
+        tree::HashTree::new(
+            core::any::type_name::<Self>(),
+            Self::TYPE_SALT,
+            checksum.value(),
+        )
+        .with_children(children)
+    }
+}
+
+impl EasyHash for &str {
+    const TYPE_SALT: u32 = type_salt::<Self>();
+    fn ehash(&self) -> u64 {
+        self.ehash_in::<v1::V1>()
+    }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        ehash_str::<F>(Self::TYPE_SALT, Self::TYPE_SALT64, self)
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl EasyHash for String {
     const TYPE_SALT: u32 = type_salt::<String>();
     fn ehash(&self) -> u64 {
-        let mut checksum = fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
+        self.ehash_in::<v1::V1>()
+    }
 
-        let bytes = self.as_bytes();
-        let mut chunks = bytes.chunks_exact(4);
-        for chunk in &mut chunks {
-            checksum.update(&[u32::from_le_bytes(chunk.try_into().unwrap())]);
-        }
-        let remainder = chunks.remainder();
-        if !remainder.is_empty() {
-            let mut byte = [0u8; 4];
-            byte[..remainder.len()].copy_from_slice(remainder);
-            checksum.update(&[u32::from_le_bytes(byte)]);
-        }
-        checksum.value()
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
+        ehash_str::<F>(Self::TYPE_SALT, Self::TYPE_SALT64, self)
+    }
+}
+
+/// Hash the UTF-8 bytes of `s`, packed little-endian into `u32`s with the last one
+/// padded with zeroes.
+fn ehash_str<F: HashFormat>(salt: u32, salt64: u64, s: &str) -> u64 {
+    let mut checksum = fletcher::Fletcher64::new();
+    F::update_salt(&mut checksum, salt, salt64);
+    F::update_len(&mut checksum, s.len());
+
+    let bytes = s.as_bytes();
+    let mut chunks = bytes.chunks_exact(4);
+    for chunk in &mut chunks {
+        checksum.update(&[u32::from_le_bytes(chunk.try_into().unwrap())]);
+    }
+    let remainder = chunks.remainder();
+    if !remainder.is_empty() {
+        let mut byte = [0u8; 4];
+        byte[..remainder.len()].copy_from_slice(remainder);
+        checksum.update(&[u32::from_le_bytes(byte)]);
     }
+    F::finish(checksum.value())
 }
@@ -0,0 +1,140 @@
+//! Hashing large collections on the [`rayon`] thread pool.
+//!
+//! [`ParEasyHash::par_ehash`] gives exactly the same hash as
+//! [`EasyHash::ehash`](crate::EasyHash::ehash), however many threads there are:
+//!
+//! ```
+//! use easy_hash::{EasyHash, ParEasyHash};
+//!
+//! #[derive(EasyHash)]
+//! struct Entity {
+//!     id: u32,
+//!     position: (i32, i32),
+//! }
+//!
+//! let entities: Vec<Entity> = (0..10_000)
+//!     .map(|id| Entity { id, position: (id as i32, -(id as i32)) })
+//!     .collect();
+//! assert_eq!(entities.par_ehash(), entities.ehash());
+//! ```
+//!
+//! The collection is split into fixed-size chunks, and the checksum of each chunk
+//! is computed on its own. Fletcher's checksum is a pair of sums modulo
+//! `2^32 - 1`, so the checksums of consecutive chunks can be combined exactly into
+//! the checksum of the whole. Since the combination is associative, it does not
+//! matter how rayon groups the chunks.
+//!
+//! `BTreeMap` is hashed the same way, from its entries in key order. `HashMap` has
+//! no impl here or in `EasyHash`, since its order varies between runs.
+
+use fletcher::Fletcher64;
+use rayon::prelude::*;
+
+use crate::bytemuck_slices::unaligned_sums;
+use crate::simd::Sums;
+use crate::{EasyHash, HashFormat, u64_to_u32_slice, v1};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use bytemuck::{Pod, cast_slice, pod_align_to};
+
+/// The number of elements hashed together by one task.
+const CHUNK_LEN: usize = 4096;
+
+/// The number of words of `Pod` data checksummed together by one task.
+const CHUNK_WORDS: usize = 1 << 16;
+
+/// Parallel versions of [`EasyHash::ehash`] and [`EasyHash::ehash_in`] for large
+/// collections.
+///
+/// Implemented for `Vec<T>`, `BTreeMap<K, V>`, and slices and arrays of `Pod` values.
+pub trait ParEasyHash {
+    /// The same as [`EasyHash::ehash`], computed on the current rayon thread pool.
+    fn par_ehash(&self) -> u64 {
+        self.par_ehash_in::<v1::V1>()
+    }
+
+    /// The same as [`EasyHash::ehash_in`], computed on the current rayon thread pool.
+    fn par_ehash_in<F: HashFormat>(&self) -> u64;
+}
+
+/// Combine the sums of consecutive chunks, in order.
+fn par_sums(chunks: impl IndexedParallelIterator<Item = Sums>) -> Sums {
+    chunks.reduce(|| Sums::EMPTY, Sums::then)
+}
+
+impl<T> ParEasyHash for Vec<T>
+where
+    T: EasyHash + Sync,
+{
+    fn par_ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+
+        let elements = par_sums(self.par_chunks(CHUNK_LEN).map(|chunk| {
+            let hashes: Vec<u64> = chunk.iter().map(|x| x.ehash_in::<F>()).collect();
+            Sums::of_words(u64_to_u32_slice(&hashes))
+        }));
+        F::finish(Sums::of_checksum(&checksum, 0).then(elements).value())
+    }
+}
+
+impl<K, V> ParEasyHash for BTreeMap<K, V>
+where
+    K: EasyHash + Sync,
+    V: EasyHash + Sync,
+{
+    fn par_ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+
+        // The entries are split into chunks by position, which a map cannot do itself.
+        let entries: Vec<(&K, &V)> = self.iter().collect();
+        let elements = par_sums(entries.par_chunks(CHUNK_LEN).map(|chunk| {
+            let hashes: Vec<u64> = chunk
+                .iter()
+                .flat_map(|(k, v)| [k.ehash_in::<F>(), v.ehash_in::<F>()])
+                .collect();
+            Sums::of_words(u64_to_u32_slice(&hashes))
+        }));
+        F::finish(Sums::of_checksum(&checksum, 0).then(elements).value())
+    }
+}
+
+/// The sums of `data` as `pod_sums` computes them in the format `F`.
+fn par_pod_sums<F: HashFormat, T: Pod + Sync>(data: &[T]) -> Sums {
+    if F::ADDRESS_INDEPENDENT {
+        let bytes: &[u8] = cast_slice(data);
+        return par_sums(bytes.par_chunks(4 * CHUNK_WORDS).map(unaligned_sums));
+    }
+
+    let (head, body, tail) = pod_align_to::<T, u32>(data);
+    let body = par_sums(body.par_chunks(CHUNK_WORDS).map(Sums::of_words));
+    unaligned_sums(head).then(body).then(unaligned_sums(tail))
+}
+
+impl<T, const N: usize> ParEasyHash for [T; N]
+where
+    T: Pod + Sync,
+{
+    fn par_ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        let data = par_pod_sums::<F, T>(self);
+        F::finish(Sums::of_checksum(&checksum, 0).then(data).value())
+    }
+}
+
+impl<T> ParEasyHash for [T]
+where
+    T: Pod + Sync,
+{
+    fn par_ehash_in<F: HashFormat>(&self) -> u64 {
+        let mut checksum = Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+        F::update_len(&mut checksum, self.len());
+        let data = par_pod_sums::<F, T>(self);
+        F::finish(Sums::of_checksum(&checksum, 0).then(data).value())
+    }
+}
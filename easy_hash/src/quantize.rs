@@ -0,0 +1,179 @@
+//! Tolerant hashing of floating point values.
+//!
+//! Floats computed on different machines can differ by a few ULPs, e.g. because of
+//! different instruction scheduling. Snapping them to a grid before hashing makes
+//! such values hash alike. Values that straddle a grid boundary still hash
+//! differently, so the grid should be much coarser than the expected error.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{EasyHash, OPTION_NONE_VAL, split_u64};
+use fletcher::calc_fletcher64;
+
+/// How floats are snapped before hashing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantization {
+    /// Round to the nearest multiple of the given step.
+    Step(f64),
+    /// Clear the low bits of the float's representation, so that values within
+    /// this many units in the last place (rounded up to a power of two) of each
+    /// other usually hash alike.
+    Ulps(u32),
+}
+
+impl Quantization {
+    pub fn quantize_f32(self, value: f32) -> f32 {
+        let snapped = match self {
+            Quantization::Step(step) => (round(value as f64 / step) * step) as f32,
+            Quantization::Ulps(ulps) => f32::from_bits(value.to_bits() & !ulp_mask(ulps) as u32),
+        };
+        // `-0.0 + 0.0 == 0.0`, so both zeros hash alike.
+        snapped + 0.0
+    }
+
+    pub fn quantize_f64(self, value: f64) -> f64 {
+        let snapped = match self {
+            Quantization::Step(step) => round(value / step) * step,
+            Quantization::Ulps(ulps) => f64::from_bits(value.to_bits() & !ulp_mask(ulps)),
+        };
+        snapped + 0.0
+    }
+}
+
+#[cfg(feature = "std")]
+fn round(value: f64) -> f64 {
+    value.round()
+}
+
+#[cfg(not(feature = "std"))]
+fn round(value: f64) -> f64 {
+    round_half_away_from_zero(value)
+}
+
+/// `f64::round`, which is not available in `core`. Values equal to `round` except
+/// that the sign of a zero result may differ, which quantizing discards.
+#[cfg(any(not(feature = "std"), test))]
+fn round_half_away_from_zero(value: f64) -> f64 {
+    // Values at least this large are integers, as are infinities; NaN stays NaN.
+    const INTEGRAL: f64 = (1u64 << 52) as f64;
+    if !(value > -INTEGRAL && value < INTEGRAL) {
+        return value;
+    }
+    let truncated = value as i64 as f64;
+    let fraction = value - truncated;
+    if fraction >= 0.5 {
+        truncated + 1.0
+    } else if fraction <= -0.5 {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+fn ulp_mask(ulps: u32) -> u64 {
+    (ulps.max(1) as u64).next_power_of_two() - 1
+}
+
+/// Hashing with the floats of a value snapped to a grid first.
+///
+/// The result is the `ehash()` of the snapped value, so the type salt is unchanged.
+/// This is what the `#[easy_hash(quantize = ...)]` and `#[easy_hash(ulps = ...)]`
+/// field options of the derive call.
+pub trait EasyHashQuantized: EasyHash {
+    fn ehash_quantized(&self, quantization: Quantization) -> u64;
+}
+
+impl EasyHashQuantized for f32 {
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        quantization.quantize_f32(*self).ehash()
+    }
+}
+
+impl EasyHashQuantized for f64 {
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        quantization.quantize_f64(*self).ehash()
+    }
+}
+
+impl<T> EasyHashQuantized for &T
+where
+    T: EasyHashQuantized,
+{
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        (**self).ehash_quantized(quantization)
+    }
+}
+
+impl<T> EasyHashQuantized for Option<T>
+where
+    T: EasyHashQuantized,
+{
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        if let Some(x) = self {
+            let parts = split_u64(x.ehash_quantized(quantization));
+            calc_fletcher64(&[Self::TYPE_SALT, parts[0], parts[1]])
+        } else {
+            calc_fletcher64(&[Self::TYPE_SALT, OPTION_NONE_VAL])
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> EasyHashQuantized for Vec<T>
+where
+    T: EasyHashQuantized,
+{
+    fn ehash_quantized(&self, quantization: Quantization) -> u64 {
+        let mut checksum = fletcher::Fletcher64::new();
+        checksum.update(&[Self::TYPE_SALT]);
+        let hashes: Vec<u64> = self
+            .iter()
+            .map(|x| x.ehash_quantized(quantization))
+            .collect();
+        checksum.update(crate::u64_to_u32_slice(&hashes));
+        checksum.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::round_half_away_from_zero;
+    use proptest::prelude::*;
+
+    #[test]
+    fn round_matches_std_at_edges() {
+        for value in [
+            0.0,
+            -0.0,
+            0.5,
+            -0.5,
+            1.5,
+            2.5,
+            -2.5,
+            0.49999999999999994,
+            4503599627370495.5,
+            -4503599627370495.5,
+            4503599627370497.0,
+            f64::MAX,
+            f64::MIN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            assert_eq!(round_half_away_from_zero(value), value.round(), "{value}");
+        }
+        assert!(round_half_away_from_zero(f64::NAN).is_nan());
+    }
+
+    proptest! {
+        #[test]
+        fn round_matches_std(value: f64) {
+            let rounded = round_half_away_from_zero(value);
+            if value.is_nan() {
+                prop_assert!(rounded.is_nan());
+            } else {
+                prop_assert_eq!(rounded + 0.0, value.round() + 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Recording hashes to a binary log, e.g. once per frame of a simulation, so that
+//! runs can be compared offline.
+//!
+//! # Format
+//!
+//! All integers are little-endian regardless of the platform, and strings are
+//! UTF-8 prefixed with their length in bytes as a `u32`.
+//!
+//! A log starts with a header:
+//!
+//! | Field   | Type       | Value                         |
+//! |---------|------------|-------------------------------|
+//! | magic   | `[u8; 8]`  | `b"EHASHLOG"`                 |
+//! | version | `u32`      | [`FORMAT_VERSION`], currently 1 |
+//!
+//! followed by any number of records until the end of the file:
+//!
+//! | Field | Type     | Notes                                   |
+//! |-------|----------|-----------------------------------------|
+//! | frame | `u64`    |                                         |
+//! | label | string   |                                         |
+//! | hash  | `u64`    |                                         |
+//! | tree  | `u8`     | 0 if no tree follows, 1 if one does     |
+//! | node  | tree node | only present if `tree` is 1            |
+//!
+//! A tree node is a [`HashTree`] written depth first:
+//!
+//! | Field      | Type      |
+//! |------------|-----------|
+//! | name       | string    |
+//! | type name  | string    |
+//! | type salt  | `u32`     |
+//! | hash       | `u64`     |
+//! | children   | `u32` count, followed by that many tree nodes |
+//!
+//! Readers reject logs with a different magic or a newer version, and trees nested
+//! more than [`MAX_TREE_DEPTH`] levels deep.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::EasyHash;
+use crate::tree::HashTree;
+
+/// Identifies a hash log.
+pub const MAGIC: [u8; 8] = *b"EHASHLOG";
+
+/// The version of the format written by [`HashRecorder`].
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The deepest tree a [`RecordReader`] accepts, counting the root as level 1, so
+/// that a corrupt log cannot make it build a tree too deep to drop or compare.
+pub const MAX_TREE_DEPTH: usize = 1024;
+
+/// A single entry of a hash log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub frame: u64,
+    pub label: String,
+    pub hash: u64,
+    /// The full breakdown of `hash`, if it was recorded.
+    pub tree: Option<HashTree>,
+}
+
+/// Appends records to a hash log.
+pub struct HashRecorder<W: Write> {
+    writer: W,
+}
+
+impl HashRecorder<BufWriter<File>> {
+    /// Create (or truncate) the log file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        HashRecorder::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> HashRecorder<W> {
+    /// Start a log by writing its header to `writer`.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(HashRecorder { writer })
+    }
+
+    /// Record a hash computed elsewhere.
+    pub fn record(&mut self, frame: u64, label: &str, hash: u64) -> io::Result<()> {
+        self.write_entry(frame, label, hash)?;
+        self.writer.write_all(&[0])
+    }
+
+    /// Record the hash of `value`.
+    pub fn record_value<T: EasyHash + ?Sized>(
+        &mut self,
+        frame: u64,
+        label: &str,
+        value: &T,
+    ) -> io::Result<()> {
+        self.record(frame, label, value.ehash())
+    }
+
+    /// Record the hash of `value` together with its full breakdown.
+    pub fn record_tree<T: EasyHash + ?Sized>(
+        &mut self,
+        frame: u64,
+        label: &str,
+        value: &T,
+    ) -> io::Result<()> {
+        let tree = value.ehash_tree();
+        self.write_entry(frame, label, tree.hash)?;
+        self.writer.write_all(&[1])?;
+        write_node(&mut self.writer, &tree)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_entry(&mut self, frame: u64, label: &str, hash: u64) -> io::Result<()> {
+        self.writer.write_all(&frame.to_le_bytes())?;
+        write_str(&mut self.writer, label)?;
+        self.writer.write_all(&hash.to_le_bytes())
+    }
+}
+
+fn write_str(writer: &mut impl Write, s: &str) -> io::Result<()> {
+    let len = u32::try_from(s.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "string too long"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn write_node(writer: &mut impl Write, node: &HashTree) -> io::Result<()> {
+    write_str(writer, &node.name)?;
+    write_str(writer, &node.type_name)?;
+    writer.write_all(&node.type_salt.to_le_bytes())?;
+    writer.write_all(&node.hash.to_le_bytes())?;
+    let count = u32::try_from(node.children.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many children"))?;
+    writer.write_all(&count.to_le_bytes())?;
+    for child in &node.children {
+        write_node(writer, child)?;
+    }
+    Ok(())
+}
+
+/// Iterates over the records of a hash log.
+pub struct RecordReader<R: Read> {
+    reader: R,
+    version: u32,
+    /// Set after an error, since the position in the log is lost.
+    failed: bool,
+}
+
+impl RecordReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        RecordReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Check the header of the log in `reader`.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a hash log"));
+        }
+        let version = read_u32(&mut reader)?;
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported hash log version {version}"
+            )));
+        }
+        Ok(RecordReader {
+            reader,
+            version,
+            failed: false,
+        })
+    }
+
+    /// The format version of the log.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let Some(frame) = read_u64_or_eof(&mut self.reader)? else {
+            return Ok(None);
+        };
+        let label = read_string(&mut self.reader)?;
+        let hash = read_u64(&mut self.reader)?;
+        let tree = match read_u8(&mut self.reader)? {
+            0 => None,
+            1 => Some(read_tree(&mut self.reader)?),
+            flag => return Err(invalid_data(format!("invalid tree flag {flag}"))),
+        };
+        Ok(Some(Record {
+            frame,
+            label,
+            hash,
+            tree,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_record().transpose();
+        if let Some(Err(_)) = result {
+            self.failed = true;
+        }
+        result
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Read a `u64`, or `None` if the reader is at its end. Ending partway through the
+/// value is an error.
+fn read_u64_or_eof(reader: &mut impl Read) -> io::Result<Option<u64>> {
+    let mut buf = [0; 8];
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(Some(u64::from_le_bytes(buf)))
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_u32(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|_| invalid_data("string is not valid UTF-8"))
+}
+
+/// Read a tree written by `write_node`.
+///
+/// The nodes are read with an explicit stack rather than recursively, so that the
+/// depth is only limited by [`MAX_TREE_DEPTH`].
+fn read_tree(reader: &mut impl Read) -> io::Result<HashTree> {
+    // The ancestors of the next node, with the number of children each has left.
+    let mut ancestors: Vec<(HashTree, u32)> = Vec::new();
+    loop {
+        if ancestors.len() >= MAX_TREE_DEPTH {
+            return Err(invalid_data(format!(
+                "tree is nested more than {MAX_TREE_DEPTH} levels deep"
+            )));
+        }
+        let (mut node, count) = read_node(reader)?;
+        if count > 0 {
+            ancestors.push((node, count));
+            continue;
+        }
+        // Attach the finished node to its parent, finishing the parent too if this
+        // was its last child.
+        loop {
+            let Some((parent, left)) = ancestors.last_mut() else {
+                return Ok(node);
+            };
+            parent.children.push(node);
+            *left -= 1;
+            if *left > 0 {
+                break;
+            }
+            node = ancestors.pop().unwrap().0;
+        }
+    }
+}
+
+/// Read a node without its children, and the number of children that follow it.
+fn read_node(reader: &mut impl Read) -> io::Result<(HashTree, u32)> {
+    let name = read_string(reader)?;
+    let type_name = read_string(reader)?;
+    let type_salt = read_u32(reader)?;
+    let hash = read_u64(reader)?;
+    let count = read_u32(reader)?;
+    let node = HashTree {
+        name,
+        type_name: Cow::Owned(type_name),
+        type_salt,
+        hash,
+        children: Vec::new(),
+    };
+    Ok((node, count))
+}
@@ -0,0 +1,262 @@
+//! A global registry of type salts, for detecting collisions and naming salts.
+//!
+//! With the `registry` feature, every non-generic type deriving `EasyHash` and
+//! every non-generic built-in impl registers its salts through
+//! [`inventory`](https://docs.rs/inventory). Generic types can be registered for
+//! particular arguments with [`register_salts!`](crate::register_salts).
+//!
+//! Two distinct types sharing a salt hash alike whenever they are interchangeable,
+//! e.g. as the contents of an enum variant. [`assert_no_collisions`] fails a test
+//! if that happens anywhere in the binary:
+//!
+//! ```standalone_crate
+//! #[derive(easy_hash::EasyHash)]
+//! struct Player {
+//!     score: u32,
+//! }
+//!
+//! easy_hash::registry::assert_no_collisions();
+//! let registry = easy_hash::registry::SaltRegistry::global();
+//! assert_eq!(registry.name(<Player as easy_hash::EasyHash>::TYPE_SALT), Some(std::any::type_name::<Player>()));
+//! ```
+//!
+//! Hash trees and recordings carry the type name of every node already; the
+//! registry maps salts back to names where only the salt is known, e.g. in trees
+//! read back from a log written by an older build.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+pub use inventory;
+
+use crate::EasyHash;
+
+/// The salts of a single type, as submitted to the registry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaltEntry {
+    pub type_name: &'static str,
+    pub salt: u32,
+    pub salt64: u64,
+}
+
+impl SaltEntry {
+    pub const fn of<T: EasyHash + ?Sized>() -> Self {
+        SaltEntry::named::<T>(T::TYPE_SALT, T::TYPE_SALT64)
+    }
+
+    /// The entry of `T` with the given salts, for types that do not implement
+    /// `EasyHash` themselves, such as the foreign types of remote definitions.
+    pub const fn named<T: ?Sized>(salt: u32, salt64: u64) -> Self {
+        SaltEntry {
+            type_name: std::any::type_name::<T>(),
+            salt,
+            salt64,
+        }
+    }
+}
+
+inventory::collect!(SaltEntry);
+
+/// Register the salts of the given types, e.g. particular instances of a generic type.
+///
+/// ```
+/// easy_hash::register_salts!(Vec<u32>, Vec<String>);
+/// ```
+#[macro_export]
+macro_rules! register_salts {
+    ($($T:ty),+ $(,)?) => {
+        $(
+            $crate::registry::inventory::submit! {
+                $crate::registry::SaltEntry::of::<$T>()
+            }
+        )+
+    };
+}
+
+/// Salts mapped to the names of the types that use them.
+#[derive(Clone, Debug, Default)]
+pub struct SaltRegistry {
+    by_salt: BTreeMap<u32, BTreeSet<&'static str>>,
+    by_salt64: BTreeMap<u64, BTreeSet<&'static str>>,
+}
+
+impl SaltRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        SaltRegistry::default()
+    }
+
+    /// The registry of every type registered in the binary.
+    pub fn global() -> &'static SaltRegistry {
+        static GLOBAL: OnceLock<SaltRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            let mut registry = SaltRegistry::new();
+            for entry in inventory::iter::<SaltEntry> {
+                registry.insert(entry);
+            }
+            registry
+        })
+    }
+
+    /// Add a type. Registering the same type name again has no effect.
+    pub fn insert(&mut self, entry: &SaltEntry) {
+        self.by_salt
+            .entry(entry.salt)
+            .or_default()
+            .insert(entry.type_name);
+        self.by_salt64
+            .entry(entry.salt64)
+            .or_default()
+            .insert(entry.type_name);
+    }
+
+    /// The name of the type using `salt`, if exactly one registered type does.
+    pub fn name(&self, salt: u32) -> Option<&'static str> {
+        single(self.by_salt.get(&salt))
+    }
+
+    /// The name of the type using the 64-bit `salt64`, if exactly one registered
+    /// type does.
+    pub fn name64(&self, salt64: u64) -> Option<&'static str> {
+        single(self.by_salt64.get(&salt64))
+    }
+
+    /// The names of all registered types using `salt`, in alphabetical order.
+    pub fn names(&self, salt: u32) -> impl Iterator<Item = &'static str> + '_ {
+        self.by_salt.get(&salt).into_iter().flatten().copied()
+    }
+
+    /// The number of distinct registered type names.
+    pub fn len(&self) -> usize {
+        self.by_salt.values().map(BTreeSet::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_salt.is_empty()
+    }
+
+    /// Every salt shared by several type names, 32-bit salts first.
+    pub fn collisions(&self) -> Vec<SaltCollision> {
+        let narrow = self
+            .by_salt
+            .iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(&salt, names)| SaltCollision {
+                salt: Salt::Narrow(salt),
+                type_names: names.iter().copied().collect(),
+            });
+        let wide = self
+            .by_salt64
+            .iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(&salt, names)| SaltCollision {
+                salt: Salt::Wide(salt),
+                type_names: names.iter().copied().collect(),
+            });
+        narrow.chain(wide).collect()
+    }
+}
+
+fn single(names: Option<&BTreeSet<&'static str>>) -> Option<&'static str> {
+    match names {
+        Some(names) if names.len() == 1 => names.first().copied(),
+        _ => None,
+    }
+}
+
+/// A `TYPE_SALT` or `TYPE_SALT64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Salt {
+    Narrow(u32),
+    Wide(u64),
+}
+
+/// Several distinct types sharing a salt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaltCollision {
+    pub salt: Salt,
+    /// In alphabetical order.
+    pub type_names: Vec<&'static str>,
+}
+
+impl fmt::Display for SaltCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.salt {
+            Salt::Narrow(salt) => write!(f, "TYPE_SALT {salt:#010x}")?,
+            Salt::Wide(salt) => write!(f, "TYPE_SALT64 {salt:#018x}")?,
+        }
+        write!(f, " is shared by {}", self.type_names.join(", "))
+    }
+}
+
+/// Collisions between built-in types that the frozen v1 format has to keep:
+/// `f64` has always been salted with the salt of `f32`. Their 64-bit salts differ.
+pub const KNOWN_COLLISIONS: &[Salt] = &[Salt::Narrow(crate::type_salt::<f32>())];
+
+/// Panic if two registered types share a salt, listing every collision except the
+/// [`KNOWN_COLLISIONS`] of built-in types.
+pub fn assert_no_collisions() {
+    let collisions: Vec<SaltCollision> = SaltRegistry::global()
+        .collisions()
+        .into_iter()
+        .filter(|collision| !KNOWN_COLLISIONS.contains(&collision.salt))
+        .collect();
+    if !collisions.is_empty() {
+        let lines: Vec<String> = collisions.iter().map(ToString::to_string).collect();
+        panic!("type salts collide:\n{}", lines.join("\n"));
+    }
+}
+
+register_salts!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    f32,
+    f64,
+    &str,
+    String,
+    std::any::TypeId,
+);
+
+#[cfg(feature = "nalgebra")]
+register_salts!(
+    nalgebra::Vector2<f32>,
+    nalgebra::Vector3<f32>,
+    nalgebra::UnitVector2<f32>,
+    nalgebra::Point2<f32>,
+    nalgebra::Isometry2<f32>,
+);
+
+#[cfg(feature = "ordered_float")]
+register_salts!(
+    ordered_float::OrderedFloat<f32>,
+    ordered_float::OrderedFloat<f64>,
+    ordered_float::NotNan<f32>,
+    ordered_float::NotNan<f64>,
+);
+
+#[cfg(feature = "godot")]
+register_salts!(
+    godot::builtin::Vector2,
+    godot::builtin::Vector3,
+    godot::builtin::Vector2i,
+    godot::builtin::Vector3i,
+);
+
+#[cfg(feature = "rapier")]
+register_salts!(
+    rapier2d::prelude::RigidBodyHandle,
+    rapier2d::prelude::ColliderHandle,
+    rapier2d::prelude::ImpulseJointHandle,
+    rapier2d::prelude::MultibodyJointHandle,
+);
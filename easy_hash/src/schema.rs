@@ -0,0 +1,110 @@
+//! Fingerprints of the shape of hashed types, for detecting layout changes.
+//!
+//! [`EasyHash::SCHEMA_HASH`] changes whenever a type's definition does in a way
+//! that could matter to stored data: for derived types, adding, removing,
+//! renaming, reordering or retyping a field or variant, or changing its
+//! `easy_hash` options. Types that are not broken down further are fingerprinted
+//! by their type name. Storing `SCHEMA_HASH` next to saved data lets a loader
+//! reject stale data with [`check`] before deserializing it.
+//!
+//! A field whose type refers to the type being derived, as in
+//! `children: Vec<Self>`, is fingerprinted by its type as written, since its
+//! schema would depend on itself. Mutually recursive types have no `SCHEMA_HASH`;
+//! using it fails to compile.
+//!
+//! Like `TYPE_SALT`, the value is only stable for a given compiler and easy_hash
+//! version; it is not meant to identify types across releases.
+
+use core::fmt;
+
+use const_fnv1a_hash::{fnv1a_hash_64, fnv1a_hash_str_64};
+
+use crate::EasyHash;
+
+/// Fingerprint a name or other part of a schema.
+pub const fn hash_str(s: &str) -> u64 {
+    fnv1a_hash_str_64(s)
+}
+
+/// Fold `part` into the fingerprint `hash`. The order of the parts matters.
+pub const fn combine(hash: u64, part: u64) -> u64 {
+    let hash = hash.to_le_bytes();
+    let part = part.to_le_bytes();
+    let mut bytes = [0u8; 16];
+    let mut i = 0;
+    while i < 8 {
+        bytes[i] = hash[i];
+        bytes[i + 8] = part[i];
+        i += 1;
+    }
+    fnv1a_hash_64(&bytes, None)
+}
+
+/// The fingerprint of a type made of `parts`, such as a container and the schema
+/// of its elements.
+pub const fn compose<const N: usize>(name: &str, parts: [u64; N]) -> u64 {
+    let mut hash = hash_str(name);
+    let mut i = 0;
+    while i < N {
+        hash = combine(hash, parts[i]);
+        i += 1;
+    }
+    hash
+}
+
+/// The `SCHEMA_HASH` of `T`.
+///
+/// The derive reads the schemas of field types through this function, since
+/// constants used directly in a constant are evaluated even if it never is itself.
+/// That way, mutually recursive types still compile as long as their
+/// `SCHEMA_HASH` is not used.
+pub const fn of<T: EasyHash + ?Sized>() -> u64 {
+    T::SCHEMA_HASH
+}
+
+/// Stored data was written with a different schema than the current one of its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    pub type_name: &'static str,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "schema of {} changed: expected {:#018x}, found {:#018x}",
+            self.type_name, self.expected, self.found
+        )
+    }
+}
+
+impl core::error::Error for SchemaMismatch {}
+
+/// Check that `stored`, a `SCHEMA_HASH` saved with some data, matches the current
+/// schema of `T`.
+///
+/// ```
+/// use easy_hash::EasyHash;
+///
+/// #[derive(EasyHash)]
+/// struct Save {
+///     level: u32,
+/// }
+///
+/// let stored = Save::SCHEMA_HASH;
+/// assert!(easy_hash::schema::check::<Save>(stored).is_ok());
+/// assert!(easy_hash::schema::check::<Save>(stored ^ 1).is_err());
+/// ```
+pub fn check<T: EasyHash + ?Sized>(stored: u64) -> Result<(), SchemaMismatch> {
+    if stored == T::SCHEMA_HASH {
+        Ok(())
+    } else {
+        Err(SchemaMismatch {
+            type_name: core::any::type_name::<T>(),
+            expected: T::SCHEMA_HASH,
+            found: stored,
+        })
+    }
+}
@@ -0,0 +1,217 @@
+//! Golden-hash snapshots, to catch accidental changes of a value's `ehash`.
+//!
+//! [`assert_ehash_snapshot!`](crate::assert_ehash_snapshot) stores the hash of a
+//! value in `snapshots/<name>.ehash` next to the test file, along with a readable
+//! dump of the value's hash tree when the `tree` feature is enabled. Only the hash
+//! is compared; the dump is there to make reviewing a changed snapshot easier.
+//!
+//! A missing snapshot is written and the assertion passes, unless the `CI`
+//! environment variable is set. Set `EASY_HASH_UPDATE_SNAPSHOTS=1` to overwrite
+//! snapshots that no longer match.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::EasyHash;
+
+/// The environment variable that makes mismatching snapshots get overwritten.
+pub const UPDATE_ENV_VAR: &str = "EASY_HASH_UPDATE_SNAPSHOTS";
+
+const HEADER: &str = "# easy_hash snapshot";
+
+/// Assert that the `ehash` of a value matches the snapshot called `name`.
+///
+/// ```no_run
+/// use easy_hash::{EasyHash, assert_ehash_snapshot};
+///
+/// #[derive(EasyHash)]
+/// struct SaveGame {
+///     level: u32,
+///     gold: u64,
+/// }
+///
+/// assert_ehash_snapshot!("save_game", SaveGame { level: 3, gold: 120 });
+/// ```
+#[macro_export]
+macro_rules! assert_ehash_snapshot {
+    ($name:expr, $value:expr $(,)?) => {
+        $crate::snapshot::assert_snapshot(
+            &$crate::snapshot::snapshot_dir(env!("CARGO_MANIFEST_DIR"), file!()),
+            $name,
+            &$value,
+        )
+    };
+}
+
+/// What to do with a snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Compare against the snapshot, writing it only if it does not exist yet.
+    Compare,
+    /// Compare against the snapshot, failing if it does not exist.
+    Require,
+    /// Write the snapshot unless it matches already.
+    Update,
+}
+
+impl Mode {
+    /// The mode selected by the `EASY_HASH_UPDATE_SNAPSHOTS` and `CI` environment
+    /// variables.
+    pub fn from_env() -> Self {
+        let set = |name| std::env::var_os(name).is_some_and(|v| !v.is_empty() && v != "0");
+        if set(UPDATE_ENV_VAR) {
+            Mode::Update
+        } else if set("CI") {
+            Mode::Require
+        } else {
+            Mode::Compare
+        }
+    }
+}
+
+/// The result of a successful [`check`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Matched,
+    Created,
+    Updated,
+}
+
+/// Why a [`check`] failed.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Missing {
+        path: PathBuf,
+        current: String,
+    },
+    Mismatch {
+        path: PathBuf,
+        stored: String,
+        current: String,
+    },
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Missing { path, current } => write!(
+                f,
+                "ehash snapshot {} does not exist\n\ncurrent:\n{current}\n\
+                 Run with {UPDATE_ENV_VAR}=1 to create it.",
+                path.display()
+            ),
+            SnapshotError::Mismatch {
+                path,
+                stored,
+                current,
+            } => write!(
+                f,
+                "ehash snapshot {} does not match\n\nstored:\n{stored}\ncurrent:\n{current}\n\
+                 Run with {UPDATE_ENV_VAR}=1 to update it if the change is intended.",
+                path.display()
+            ),
+            SnapshotError::Io(path, err) => {
+                write!(
+                    f,
+                    "failed to access ehash snapshot {}: {err}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// The contents of the snapshot file for `value`.
+pub fn render<T: EasyHash + ?Sized>(value: &T) -> String {
+    let out = format!(
+        "{HEADER}; update with {UPDATE_ENV_VAR}=1\nehash: {:#018x}\ntype: {}\n",
+        value.ehash(),
+        std::any::type_name::<T>()
+    );
+    #[cfg(feature = "tree")]
+    let out = format!("{out}\n{}", value.ehash_tree());
+    out
+}
+
+/// The hash line of a rendered snapshot, which is all that is compared.
+fn stored_hash(snapshot: &str) -> Option<&str> {
+    snapshot.lines().find(|line| line.starts_with("ehash: "))
+}
+
+/// Compare the snapshot at `path` with `value`, creating or updating it as `mode`
+/// allows.
+pub fn check<T: EasyHash + ?Sized>(
+    path: &Path,
+    value: &T,
+    mode: Mode,
+) -> Result<Outcome, SnapshotError> {
+    let current = render(value);
+    let write = |outcome| {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| SnapshotError::Io(dir.to_path_buf(), err))?;
+        }
+        fs::write(path, &current).map_err(|err| SnapshotError::Io(path.to_path_buf(), err))?;
+        Ok(outcome)
+    };
+
+    let stored = match fs::read_to_string(path) {
+        Ok(stored) => stored,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return match mode {
+                Mode::Compare | Mode::Update => write(Outcome::Created),
+                Mode::Require => Err(SnapshotError::Missing {
+                    path: path.to_path_buf(),
+                    current,
+                }),
+            };
+        }
+        Err(err) => return Err(SnapshotError::Io(path.to_path_buf(), err)),
+    };
+
+    if stored_hash(&stored).is_some() && stored_hash(&stored) == stored_hash(&current) {
+        return Ok(Outcome::Matched);
+    }
+    match mode {
+        Mode::Update => write(Outcome::Updated),
+        Mode::Compare | Mode::Require => Err(SnapshotError::Mismatch {
+            path: path.to_path_buf(),
+            stored,
+            current,
+        }),
+    }
+}
+
+/// Used by [`assert_ehash_snapshot!`](crate::assert_ehash_snapshot).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_snapshot<T: EasyHash + ?Sized>(dir: &Path, name: &str, value: &T) {
+    let path = dir.join(format!("{name}.ehash"));
+    if let Err(err) = check(&path, value, Mode::from_env()) {
+        panic!("{err}");
+    }
+}
+
+/// The `snapshots` directory next to the source file `file` (as given by `file!()`)
+/// of the crate in `manifest_dir`.
+///
+/// `file!()` is relative to the directory cargo was invoked in, which is the
+/// workspace root for workspace members, so the ancestors of `manifest_dir` are
+/// tried as well.
+#[doc(hidden)]
+pub fn snapshot_dir(manifest_dir: &str, file: &str) -> PathBuf {
+    let file = Path::new(file);
+    let source = Path::new(manifest_dir)
+        .ancestors()
+        .map(|dir| dir.join(file))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| Path::new(manifest_dir).join(file));
+    source
+        .parent()
+        .unwrap_or(Path::new(manifest_dir))
+        .join("snapshots")
+}
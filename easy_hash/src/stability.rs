@@ -0,0 +1,198 @@
+//! Reference hashes of the built-in impls.
+//!
+//! Hashes depend on `fletcher`, `const-fnv1a-hash` and the output of
+//! `std::any::type_name`, any of which could change between versions without
+//! breaking a single property test. [`vectors`] pins the `ehash` of a fixed set of
+//! values so that such a change is noticed, both by this crate's tests and by
+//! downstream crates that persist hashes:
+//!
+//! ```
+//! for vector in easy_hash::stability::vectors() {
+//!     assert!(vector.matches(), "{vector}");
+//! }
+//! ```
+//!
+//! The values are those of little-endian targets. Pod arrays are only pinned for
+//! element types aligned to at least 4 bytes, since the hash of other Pod slices
+//! depends on their address.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::EasyHash;
+
+/// A value together with the `ehash` it is expected to have.
+#[derive(Clone, Copy)]
+pub struct TestVector {
+    /// The type and value being hashed, e.g. `u32 = 7`.
+    pub name: &'static str,
+    pub expected: u64,
+    /// Hashes the value with the current build.
+    pub compute: fn() -> u64,
+}
+
+impl TestVector {
+    /// The hash of the value with the current build.
+    pub fn actual(&self) -> u64 {
+        (self.compute)()
+    }
+
+    pub fn matches(&self) -> bool {
+        self.actual() == self.expected
+    }
+}
+
+impl fmt::Debug for TestVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestVector")
+            .field("name", &self.name)
+            .field("expected", &format_args!("{:#018x}", self.expected))
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for TestVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {:#018x}, got {:#018x}",
+            self.name,
+            self.expected,
+            self.actual()
+        )
+    }
+}
+
+macro_rules! vector {
+    ($ty:ty = $value:expr, $expected:expr) => {
+        TestVector {
+            name: concat!(stringify!($ty), " = ", stringify!($value)),
+            expected: $expected,
+            compute: || {
+                let value: $ty = $value;
+                EasyHash::ehash(&value)
+            },
+        }
+    };
+}
+
+/// The reference hashes of every built-in impl that is enabled.
+pub fn vectors() -> Vec<TestVector> {
+    #[allow(unused_mut)]
+    let mut vectors = vec![
+        vector!(bool = false, 0x9129_2a7b_c894_953d),
+        vector!(bool = true, 0x9129_2a7c_c894_953e),
+        vector!(u8 = 0, 0x1685_65f0_0b42_b2f8),
+        vector!(u8 = 7, 0x1685_65f7_0b42_b2ff),
+        vector!(u8 = u8::MAX, 0x21c8_18ef_1685_65f7),
+        vector!(u16 = 1234, 0xf3dc_3b10_79ee_1ff1),
+        vector!(u16 = u16::MAX, 0x6dcb_363e_f3dd_1b1e),
+        vector!(u32 = 7, 0x0be6_e969_05f3_74b8),
+        vector!(u32 = u32::MAX, 0x17cd_d2c4_0be6_e962),
+        vector!(u64 = 0x0123_4567_89ab_cdef, 0xf9e3_0df4_04c9_fa69),
+        vector!(u64 = u64::MAX, 0x61e6_835c_f3f5_ce24),
+        vector!(usize = 42, 0x42d5_6f4e_6b9c_7a8b),
+        vector!(i8 = -7, 0x2671_f763_9338_fbae),
+        vector!(i8 = -1, 0x2671_f769_9338_fbb4),
+        vector!(i16 = -1234, 0xad45_7ce5_56a2_bc0a),
+        vector!(i32 = -7, 0x954f_e945_caa7_f49f),
+        vector!(i32 = -1, 0x2a9f_d297_954f_e94b),
+        vector!(i64 = -0x0123_4567_89ab_cdef, 0xc7df_0e77_3bcc_0f11),
+        vector!(i64 = -1, 0xe107_ac01_8d36_44cd),
+        vector!(isize = -42, 0x7caa_044e_298e_0154),
+        vector!(f32 = 1.5, 0x482e_bd89_c3f7_5ec4),
+        vector!(f32 = -0.0, 0x886e_bd89_0437_5ec5),
+        vector!(f64 = 1.5, 0x0c96_1c4e_c42f_5ec4),
+        vector!(f64 = -0.25, 0x0c46_1c4f_4407_5ec5),
+        vector!(() = (), 0x28d9_f59a_28d9_f59a),
+        vector!((u32,) = (7,), 0xd76c_3113_fc64_7d89),
+        vector!((u8, i64) = (7, -7), 0x0850_95fe_c724_3827),
+        vector!(
+            (bool, f32, String) = (true, 1.5, "tuple".to_string()),
+            0xf697_9b45_db1a_ab2f
+        ),
+        vector!(&str = "", 0x3b26_76c4_3b26_76c4),
+        vector!(&str = "abc", 0x76b0_4fe9_3b89_d925),
+        vector!(&str = "easy_hash", 0x3fb6_d068_27fb_40f1),
+        vector!(String = "easy_hash".to_string(), 0x9539_5a6f_fd5b_e372),
+        vector!(Option<u32> = None, 0x3a6c_cc4a_3479_5799),
+        vector!(Option<u32> = Some(7), 0x2f9b_a59d_17cd_d2d2),
+        vector!(Option<String> = Some("some".to_string()), 0xb481_188a_0cf7_43ff),
+        vector!(Vec<u32> = vec![], 0x1848_bcc6_1848_bcc6),
+        vector!(Vec<u32> = vec![1, 2, 3], 0x5c84_d64b_4dd7_d70b),
+        vector!(Vec<String> = vec!["a".to_string(), "bc".to_string()], 0x8153_85f4_1b8e_5d6e),
+        vector!(Vec<Option<i32>> = vec![Some(-1), None], 0x397b_6d91_10cd_5615),
+        vector!(
+            BTreeMap<u32, String> = [(2, "b".to_string()), (1, "a".to_string())].into(),
+            0x6b45_162d_2003_d577
+        ),
+        vector!([u32; 4] = [1, 2, 3, 4], 0xe1da_6727_c6c5_47da),
+        vector!([i32; 3] = [-1, 0, 1], 0xa0e8_33cd_283a_0cf4),
+        vector!([f32; 2] = [0.5, -2.0], 0x838b_0a14_c0d9_035c),
+        vector!([u64; 2] = [u64::MAX, 1], 0x1972_93e7_d1e3_b72e),
+        // The salt and the word add up to `2^32 - 1`, so the first sum is 0.
+        vector!(
+            [u32; 1] = [u32::MAX - <[u32; 1] as EasyHash>::TYPE_SALT],
+            0x8ab8_1d69_0000_0000
+        ),
+        vector!(core::marker::PhantomData<u32> = core::marker::PhantomData, 0xa1da_245b_a1da_245b),
+    ];
+
+    #[cfg(feature = "nalgebra")]
+    {
+        use nalgebra::{Isometry2, Point2, UnitVector2, Vector2, Vector3};
+        vectors.extend([
+            vector!(Vector2<f32> = Vector2::new(1.0, -2.0), 0x81dc_62b5_15c9_763d),
+            vector!(Vector3<f32> = Vector3::new(1.0, -2.0, 0.5), 0x5761_cc04_b4f8_7301),
+            vector!(UnitVector2<f32> = UnitVector2::new_normalize(Vector2::new(3.0, 4.0)), 0x9d84_772d_c912_8e20),
+            vector!(Point2<f32> = Point2::new(1.0, -2.0), 0x8cf3_476f_6ed1_17d0),
+            // A rotation of 0, since `sin` and `cos` of other angles may differ by platform.
+            vector!(Isometry2<f32> = Isometry2::new(Vector2::new(1.0, -2.0), 0.0), 0xfa5c_317e_4b45_a380),
+        ]);
+    }
+
+    #[cfg(feature = "ordered_float")]
+    {
+        use ordered_float::{NotNan, OrderedFloat};
+        vectors.extend([
+            vector!(OrderedFloat<f32> = OrderedFloat(1.5), 0xc2c1_3e42_8140_9f21),
+            vector!(OrderedFloat<f64> = OrderedFloat(1.5), 0x5e9c_7c7a_df86_d428),
+            vector!(NotNan<f32> = NotNan::new(1.5).unwrap(), 0xcecf_f849_0747_fc25),
+            vector!(NotNan<f64> = NotNan::new(1.5).unwrap(), 0x087b_550d_c2d1_1c59),
+        ]);
+    }
+
+    // The salts of these types include the module godot and rapier define them in,
+    // so moving a type within those crates changes its hash.
+    #[cfg(feature = "godot")]
+    {
+        use godot::builtin::{Vector2, Vector3};
+        vectors.extend([
+            vector!(Vector2 = Vector2::new(1.0, -2.0), 0xd04b_058e_2fee_5730),
+            vector!(
+                Vector3 = Vector3::new(1.0, -2.0, 0.5),
+                0x196d_91f9_e57b_647e
+            ),
+        ]);
+    }
+
+    #[cfg(feature = "rapier")]
+    {
+        use rapier2d::prelude::{ColliderHandle, RigidBodyHandle};
+        vectors.extend([
+            vector!(
+                RigidBodyHandle = RigidBodyHandle::from_raw_parts(7, 1),
+                0x2687_babb_b782_9396
+            ),
+            vector!(
+                ColliderHandle = ColliderHandle::from_raw_parts(3, 2),
+                0x994e_9aa9_ddc4_de3a
+            ),
+        ]);
+    }
+
+    vectors
+}
@@ -1,22 +1,28 @@
-use std::cell::OnceCell;
+use core::cell::OnceCell;
 
-use crate::{EasyHash, split_u64, type_salt};
-use fletcher::calc_fletcher64;
+use crate::{EasyHash, HashFormat, schema, split_u64, type_salt, v1};
 
 impl<T> EasyHash for OnceCell<T>
 where
     T: EasyHash,
 {
     const TYPE_SALT: u32 = type_salt::<&T>();
+    const SCHEMA_HASH: u64 = schema::compose("OnceCell", [T::SCHEMA_HASH]);
 
     fn ehash(&self) -> u64 {
+        self.ehash_in::<v1::V1>()
+    }
+
+    fn ehash_in<F: HashFormat>(&self) -> u64 {
         const NONE_VAL: u32 = 961_157_112;
 
+        let mut checksum = fletcher::Fletcher64::new();
+        F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
         if let Some(x) = self.get() {
-            let parts = split_u64(x.ehash());
-            calc_fletcher64(&[Self::TYPE_SALT, parts[0], parts[1]])
+            checksum.update(&split_u64(x.ehash_in::<F>()));
         } else {
-            calc_fletcher64(&[Self::TYPE_SALT, NONE_VAL])
+            checksum.update(&[NONE_VAL]);
         }
+        F::finish(checksum.value())
     }
 }
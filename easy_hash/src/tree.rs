@@ -0,0 +1,260 @@
+//! Hash explanation trees, for finding out which part of a value changed its hash.
+//!
+//! `EasyHash::ehash_tree` builds the tree with the same traversal as `ehash`, so the
+//! root's hash always equals `ehash()`. Derived types get a node per field (and per
+//! enum variant), `Vec` a node per element, `BTreeMap` a node per entry with its
+//! key and value below it, and `Option` and tuples a node per inner value. Every
+//! other type is a leaf.
+//!
+//! [`diff`] compares the trees of two values and reports where they diverge.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::EasyHash;
+
+/// The children of a node. Derived impls name the type through this alias rather
+/// than `std::vec::Vec`, since the crate deriving `EasyHash` need not link `std`
+/// itself, even when another crate in the build enables `tree`.
+pub type Children = Vec<HashTree>;
+
+/// The hash of a value together with the hashes it was computed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashTree {
+    /// Field name, tuple index, `[index]` for elements, or variant name; empty for
+    /// the root.
+    pub name: String,
+    /// Borrowed for trees built by `ehash_tree`, owned for trees read back from a
+    /// recording.
+    pub type_name: Cow<'static, str>,
+    pub type_salt: u32,
+    pub hash: u64,
+    pub children: Vec<HashTree>,
+}
+
+impl HashTree {
+    pub fn new(type_name: &'static str, type_salt: u32, hash: u64) -> Self {
+        HashTree {
+            name: String::new(),
+            type_name: Cow::Borrowed(type_name),
+            type_salt,
+            hash,
+            children: Vec::new(),
+        }
+    }
+
+    /// The name of the type registered for this node's salt. It can differ from
+    /// `type_name` in trees read back from a log written by another build.
+    #[cfg(feature = "registry")]
+    pub fn registered_type_name(&self) -> Option<&'static str> {
+        crate::registry::SaltRegistry::global().name(self.type_salt)
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<HashTree>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Iterate over this node and all of its descendants, depth first, together
+    /// with their paths relative to this node (e.g. `body.items[2].hp`).
+    pub fn iter(&self) -> impl Iterator<Item = (String, &HashTree)> {
+        let mut stack = vec![(String::new(), self)];
+        std::iter::from_fn(move || {
+            let (path, node) = stack.pop()?;
+            for child in node.children.iter().rev() {
+                stack.push((join_path(&path, &child.name), child));
+            }
+            Some((path, node))
+        })
+    }
+
+    /// Find the descendant at `path`, as produced by [`HashTree::iter`].
+    pub fn get(&self, path: &str) -> Option<&HashTree> {
+        self.iter().find(|(p, _)| p == path).map(|(_, node)| node)
+    }
+
+    /// Find the deepest paths at which this tree and `other` differ.
+    pub fn diff(&self, other: &HashTree) -> HashDiff {
+        let mut differences = Vec::new();
+        diff_nodes(String::new(), self, other, &mut differences);
+        HashDiff { differences }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        if self.name.is_empty() {
+            writeln!(f, "{:indent$}{} = {:#018x}", "", self.type_name, self.hash)?;
+        } else {
+            writeln!(
+                f,
+                "{:indent$}{}: {} = {:#018x}",
+                "", self.name, self.type_name, self.hash
+            )?;
+        }
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where two hash trees diverge, as returned by [`diff`] and [`HashTree::diff`].
+///
+/// Differences are listed in depth first order, so the first one is the first
+/// divergent path in field order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashDiff {
+    pub differences: Vec<Difference>,
+}
+
+/// A path whose hash differs while none of its children explain the difference,
+/// e.g. a changed leaf, a different enum variant, or an element only one side has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub path: String,
+    /// The subtree at `path` on the left side, or `None` if it only exists on the right.
+    pub left: Option<HashTree>,
+    /// The subtree at `path` on the right side, or `None` if it only exists on the left.
+    pub right: Option<HashTree>,
+}
+
+impl HashDiff {
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&Difference> {
+        self.differences.first()
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.differences.iter().map(|d| d.path.as_str())
+    }
+}
+
+/// Compare the hash trees of two values.
+///
+/// ```
+/// use easy_hash::EasyHash;
+///
+/// #[derive(EasyHash)]
+/// struct Velocity {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let diff = easy_hash::diff(&Velocity { x: 1, y: 2 }, &Velocity { x: 1, y: 3 });
+/// assert_eq!(diff.paths().collect::<Vec<_>>(), ["y"]);
+/// ```
+pub fn diff<T: EasyHash + ?Sized>(left: &T, right: &T) -> HashDiff {
+    left.ehash_tree().diff(&right.ehash_tree())
+}
+
+fn diff_nodes(path: String, left: &HashTree, right: &HashTree, out: &mut Vec<Difference>) {
+    if left.hash == right.hash && left.type_salt == right.type_salt {
+        return;
+    }
+
+    let find = |children: &'_ [HashTree], name: &str| -> Option<usize> {
+        children.iter().position(|child| child.name == name)
+    };
+    let shares_children = left
+        .children
+        .iter()
+        .any(|child| find(&right.children, &child.name).is_some());
+
+    // Without children in common (leaves, different variants, `Some` against `None`),
+    // the difference cannot be narrowed down any further.
+    if !shares_children {
+        out.push(Difference {
+            path,
+            left: Some(left.clone()),
+            right: Some(right.clone()),
+        });
+        return;
+    }
+
+    let start = out.len();
+    for child in &left.children {
+        let child_path = join_path(&path, &child.name);
+        match find(&right.children, &child.name) {
+            Some(i) => diff_nodes(child_path, child, &right.children[i], out),
+            None => out.push(Difference {
+                path: child_path,
+                left: Some(child.clone()),
+                right: None,
+            }),
+        }
+    }
+    for child in &right.children {
+        if find(&left.children, &child.name).is_none() {
+            out.push(Difference {
+                path: join_path(&path, &child.name),
+                left: None,
+                right: Some(child.clone()),
+            });
+        }
+    }
+
+    // All children match, so the difference lies in how they were combined.
+    if out.len() == start {
+        out.push(Difference {
+            path,
+            left: Some(left.clone()),
+            right: Some(right.clone()),
+        });
+    }
+}
+
+/// Append a child's name to the path of its parent.
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() || name.starts_with('[') {
+        format!("{parent}{name}")
+    } else {
+        format!("{parent}.{name}")
+    }
+}
+
+impl fmt::Display for HashTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl fmt::Display for HashDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no differences");
+        }
+        for difference in &self.differences {
+            writeln!(f, "{difference}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "<root>"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: ")?;
+        fmt_side(f, self.left.as_ref())?;
+        write!(f, " -> ")?;
+        fmt_side(f, self.right.as_ref())
+    }
+}
+
+fn fmt_side(f: &mut fmt::Formatter<'_>, node: Option<&HashTree>) -> fmt::Result {
+    match node {
+        Some(node) => write!(f, "{} {:#018x}", node.type_name, node.hash),
+        None => write!(f, "(missing)"),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{EasyHash, join_u32s, type_salt, u64_to_u32_slice};
+use crate::{EasyHash, HashFormat, join_u32s, type_salt, u64_to_u32_slice, v1};
 
 impl EasyHash for () {
     const TYPE_SALT: u32 = type_salt::<Self>();
@@ -13,12 +13,17 @@ macro_rules! easy_hash_tuple_impl {
         #[allow(non_snake_case)]
         impl< $($T: EasyHash),+ > EasyHash for ( $($T,)+ ) {
             const TYPE_SALT: u32 = type_salt::<( $($T,)+ )>();
+            const SCHEMA_HASH: u64 = crate::schema::compose("tuple", [ $( $T::SCHEMA_HASH ),+ ]);
 
             fn ehash(&self) -> u64 {
+                self.ehash_in::<v1::V1>()
+            }
+
+            fn ehash_in<F: HashFormat>(&self) -> u64 {
                 // 1) Make a new Fletcher64 checksum
                 let mut checksum = fletcher::Fletcher64::new();
                 // 2) Feed in the per‐type salt
-                checksum.update(&[ Self::TYPE_SALT ]);
+                F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
 
                 // 3) Destructure self=(…) into bindings named after each type identifier:
                 //
@@ -27,9 +32,23 @@ macro_rules! easy_hash_tuple_impl {
                 // Now `T0`, `T1`, … are local references to the tuple’s elements.
                 let ( $( ref $T, )+ ) = *self;
 
-                // 4) Build an array of each element’s `ehash()` and feed into the checksum.
-                checksum.update(u64_to_u32_slice(&[ $( $T.ehash() ),+ ]));
-                checksum.value()
+                // 4) Build an array of each element’s hash and feed into the checksum.
+                checksum.update(u64_to_u32_slice(&[ $( $T.ehash_in::<F>() ),+ ]));
+                F::finish(checksum.value())
+            }
+
+            #[cfg(feature = "tree")]
+            fn ehash_tree(&self) -> crate::tree::HashTree {
+                let ( $( ref $T, )+ ) = *self;
+                // Name each element by its index, i.e. the type parameter without the `T`.
+                let children = vec![ $( $T.ehash_tree().with_name(&stringify!($T)[1..]) ),+ ];
+                let hashes: Vec<u64> = children.iter().map(|child| child.hash).collect();
+
+                let mut checksum = fletcher::Fletcher64::new();
+                checksum.update(&[ Self::TYPE_SALT ]);
+                checksum.update(u64_to_u32_slice(&hashes));
+                crate::tree::HashTree::new(core::any::type_name::<Self>(), Self::TYPE_SALT, checksum.value())
+                    .with_children(children)
             }
         }
     };
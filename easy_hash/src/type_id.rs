@@ -1,4 +1,4 @@
-use std::{
+use core::{
     any::TypeId,
     hash::{Hash, Hasher},
 };
@@ -40,7 +40,7 @@ impl EasyHash for TypeId {
 #[cfg(test)]
 mod tests {
     use super::IdentityHasher;
-    use std::hash::Hasher;
+    use core::hash::Hasher;
 
     #[test]
     #[should_panic(
@@ -1,32 +1,77 @@
-use const_fnv1a_hash::{fnv1a_hash_32, fnv1a_hash_str_64};
+//! Salts combining the names of several types, e.g. a generic type and its
+//! parameters.
+
+use crate::type_salt64;
+
+const FNV32_OFFSET: u32 = 0x811c_9dc5;
+const FNV32_PRIME: u32 = 0x0100_0193;
+const FNV64_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV64_PRIME: u64 = 0x0000_0100_0000_01b3;
 
 pub const fn type_salt_generic<T, G>() -> u32 {
-    let main_hash = fnv1a_hash_str_64(std::any::type_name::<T>());
-    let generic_hash = fnv1a_hash_str_64(std::any::type_name::<G>());
+    combine_salts(&[type_salt64::<T>(), type_salt64::<G>()])
+}
 
-    // Combine the two u64 hashes into a single u32 by converting to bytes and hashing
-    let main_bytes = main_hash.to_ne_bytes();
-    let generic_bytes = generic_hash.to_ne_bytes();
+/// Combine 64-bit type name hashes into a 32-bit salt, by hashing their native-endian
+/// bytes with FNV-1a. See [`type_salt_of!`](crate::type_salt_of).
+pub const fn combine_salts(hashes: &[u64]) -> u32 {
+    let mut salt = FNV32_OFFSET;
+    let mut i = 0;
+    while i < hashes.len() {
+        let bytes = hashes[i].to_ne_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            salt ^= bytes[j] as u32;
+            salt = salt.wrapping_mul(FNV32_PRIME);
+            j += 1;
+        }
+        i += 1;
+    }
+    salt
+}
 
-    // YUCK! written by llm, but works in const fn context
-    let combined = [
-        main_bytes[0],
-        main_bytes[1],
-        main_bytes[2],
-        main_bytes[3],
-        main_bytes[4],
-        main_bytes[5],
-        main_bytes[6],
-        main_bytes[7],
-        generic_bytes[0],
-        generic_bytes[1],
-        generic_bytes[2],
-        generic_bytes[3],
-        generic_bytes[4],
-        generic_bytes[5],
-        generic_bytes[6],
-        generic_bytes[7],
-    ];
+/// Combine 64-bit type name hashes into a 64-bit salt, by hashing their
+/// little-endian bytes with FNV-1a. See [`type_salt64_of!`](crate::type_salt64_of).
+pub const fn combine_salts64(hashes: &[u64]) -> u64 {
+    let mut salt = FNV64_OFFSET;
+    let mut i = 0;
+    while i < hashes.len() {
+        let bytes = hashes[i].to_le_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            salt ^= bytes[j] as u64;
+            salt = salt.wrapping_mul(FNV64_PRIME);
+            j += 1;
+        }
+        i += 1;
+    }
+    salt
+}
+
+/// The 32-bit salt of any number of types, in order, usable in constants.
+///
+/// `type_salt_of!(T, G)` is the same as `type_salt_generic::<T, G>()`.
+///
+/// ```
+/// const SALT: u32 = easy_hash::type_salt_of!(Vec<u8>, u16, String);
+/// assert_ne!(SALT, easy_hash::type_salt_of!(Vec<u8>, String, u16));
+/// ```
+#[macro_export]
+macro_rules! type_salt_of {
+    ($($T:ty),+ $(,)?) => {
+        $crate::type_salt_generic::combine_salts(&[$($crate::type_salt64::<$T>()),+])
+    };
+}
 
-    fnv1a_hash_32(&combined, None)
+/// The 64-bit salt of any number of types, in order, usable in constants.
+///
+/// ```
+/// const SALT: u64 = easy_hash::type_salt64_of!(Vec<u8>, u16, String);
+/// assert_ne!(SALT, easy_hash::type_salt64_of!(Vec<u8>, String, u16));
+/// ```
+#[macro_export]
+macro_rules! type_salt64_of {
+    ($($T:ty),+ $(,)?) => {
+        $crate::type_salt_generic::combine_salts64(&[$($crate::type_salt64::<$T>()),+])
+    };
 }
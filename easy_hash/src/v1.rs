@@ -0,0 +1,29 @@
+//! The original hash format, used by [`EasyHash::ehash`](crate::EasyHash::ehash).
+//!
+//! Values are hashed with Fletcher-64 and nothing else. Sequences are not
+//! prefixed with their length, and the hash of `Pod` slices depends on their
+//! alignment in memory. This format is frozen.
+
+use crate::EasyHash;
+use crate::format::HashFormat;
+
+/// The original hash format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct V1;
+
+impl HashFormat for V1 {
+    const VERSION: u32 = 1;
+    const LENGTH_PREFIX: bool = false;
+    const ADDRESS_INDEPENDENT: bool = false;
+    const WIDE_SALTS: bool = false;
+
+    #[inline]
+    fn finish(checksum: u64) -> u64 {
+        checksum
+    }
+}
+
+/// Hash `value` in the v1 format; the same as `value.ehash()`.
+pub fn ehash<T: EasyHash + ?Sized>(value: &T) -> u64 {
+    value.ehash_in::<V1>()
+}
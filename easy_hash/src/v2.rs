@@ -0,0 +1,42 @@
+//! The second hash format, which fixes the known weaknesses of [`v1`](crate::v1).
+//!
+//! - Sequences hash their length before their elements.
+//! - `Pod` slices are hashed the same wherever they are in memory.
+//! - Values are salted with 64-bit type salts, which are far less likely to
+//!   collide than the 32-bit ones.
+//! - Every checksum goes through a final mixer, so that hashes of similar values
+//!   differ in more than a few bits.
+//!
+//! **This format is not frozen yet.** Its hashes may change until it is declared
+//! stable; do not persist them before that.
+
+use crate::EasyHash;
+use crate::format::HashFormat;
+
+/// The second hash format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct V2;
+
+impl HashFormat for V2 {
+    const VERSION: u32 = 2;
+    const LENGTH_PREFIX: bool = true;
+    const ADDRESS_INDEPENDENT: bool = true;
+    const WIDE_SALTS: bool = true;
+
+    /// The 64-bit finalizer of MurmurHash3.
+    #[inline]
+    fn finish(checksum: u64) -> u64 {
+        let mut h = checksum;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^= h >> 33;
+        h
+    }
+}
+
+/// Hash `value` in the v2 format.
+pub fn ehash<T: EasyHash + ?Sized>(value: &T) -> u64 {
+    value.ehash_in::<V2>()
+}
@@ -0,0 +1,49 @@
+//! Types that declare the version of their layout.
+//!
+//! `#[easy_hash(version = N)]` folds `N` into the hash of a derived type, so that
+//! bumping it changes every hash even if the fields look alike. Older layouts can
+//! be registered with `previous(v1 = "path", ...)`, where each `path` is a
+//! `fn(&Self) -> u64` returning the hash the value would have had as that version.
+//! That lets checksums stored before a migration be verified against the
+//! migrated data:
+//!
+//! ```
+//! use easy_hash::{EasyHash, EasyHashVersioned};
+//!
+//! #[derive(EasyHash)]
+//! #[easy_hash(version = 1)]
+//! struct SaveV1 {
+//!     gold: u32,
+//! }
+//!
+//! #[derive(EasyHash)]
+//! #[easy_hash(version = 2, previous(v1 = "Save::hash_v1"))]
+//! struct Save {
+//!     gold: u64,
+//!     gems: u64,
+//! }
+//!
+//! impl Save {
+//!     fn hash_v1(&self) -> u64 {
+//!         SaveV1 { gold: self.gold as u32 }.ehash()
+//!     }
+//! }
+//!
+//! let stored = SaveV1 { gold: 10 }.ehash();
+//! let migrated = Save { gold: 10, gems: 0 };
+//! assert_eq!(migrated.ehash_as_version(1), Some(stored));
+//! assert_eq!(migrated.ehash_as_version(2), Some(migrated.ehash()));
+//! assert_eq!(migrated.ehash_as_version(3), None);
+//! ```
+
+use crate::EasyHash;
+
+/// A type deriving `EasyHash` with `#[easy_hash(version = N)]`.
+pub trait EasyHashVersioned: EasyHash {
+    /// The current version of the type's layout.
+    const VERSION: u32;
+
+    /// The `ehash()` the value would have had as `version` of its type, or `None` if
+    /// no hash function was registered for that version.
+    fn ehash_as_version(&self, version: u32) -> Option<u64>;
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::BTreeMap;
+
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::v2::V2;
+use easy_hash::{EasyHash, u64_to_u32_slice};
+
+#[derive(EasyHash)]
+struct Inventory {
+    owner: String,
+    #[easy_hash(group = "stock")]
+    counts: BTreeMap<String, u32>,
+}
+
+fn counts(entries: &[(&str, u32)]) -> BTreeMap<String, u32> {
+    entries
+        .iter()
+        .map(|(name, count)| (name.to_string(), *count))
+        .collect()
+}
+
+#[test]
+fn test_btree_map_hash_matches_manual() {
+    let map = counts(&[("b", 2), ("a", 1)]);
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[BTreeMap::<String, u32>::TYPE_SALT]);
+    checksum.update(u64_to_u32_slice(&[
+        "a".to_string().ehash(),
+        1u32.ehash(),
+        "b".to_string().ehash(),
+        2u32.ehash(),
+    ]));
+    assert_eq!(map.ehash(), checksum.value());
+}
+
+#[test]
+fn test_btree_map_hash_does_not_depend_on_insertion_order() {
+    assert_eq!(
+        counts(&[("a", 1), ("b", 2), ("c", 3)]).ehash(),
+        counts(&[("c", 3), ("a", 1), ("b", 2)]).ehash()
+    );
+}
+
+#[test]
+fn test_btree_map_hash_depends_on_keys_and_values() {
+    let map = counts(&[("a", 1), ("b", 2)]);
+    assert_ne!(map.ehash(), counts(&[("a", 1), ("c", 2)]).ehash());
+    assert_ne!(map.ehash(), counts(&[("a", 1), ("b", 3)]).ehash());
+    // Keys and values are not interchangeable.
+    let swapped: BTreeMap<u32, u32> = BTreeMap::from([(1, 2)]);
+    assert_ne!(swapped.ehash(), BTreeMap::from([(2u32, 1u32)]).ehash());
+    assert_ne!(map.ehash_in::<V2>(), counts(&[("a", 1)]).ehash_in::<V2>());
+}
+
+#[test]
+fn test_btree_map_passes_groups_through() {
+    let a = Inventory {
+        owner: "ada".to_string(),
+        counts: counts(&[("apple", 3)]),
+    };
+    let b = Inventory {
+        owner: "bob".to_string(),
+        counts: counts(&[("apple", 3)]),
+    };
+    let c = Inventory {
+        owner: "ada".to_string(),
+        counts: counts(&[("apple", 4)]),
+    };
+    assert_eq!(a.ehash_group("stock"), b.ehash_group("stock"));
+    assert_ne!(a.ehash_group("stock"), c.ehash_group("stock"));
+}
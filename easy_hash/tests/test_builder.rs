@@ -0,0 +1,96 @@
+#![cfg(feature = "alloc")]
+
+use easy_hash::fletcher::calc_fletcher64;
+use easy_hash::v2::V2;
+use easy_hash::{EasyHash, HashBuilder, ehash, split_u64, type_salt};
+
+#[derive(EasyHash)]
+struct Player {
+    name: String,
+    score: u32,
+    position: (i32, i32),
+}
+
+fn player() -> Player {
+    Player {
+        name: "ada".to_string(),
+        score: 3,
+        position: (-1, 2),
+    }
+}
+
+#[test]
+fn test_builder_matches_derive() {
+    let player = player();
+    let built = HashBuilder::new()
+        .salt(Player::TYPE_SALT)
+        .field(&player.name)
+        .field(&player.score)
+        .field(&player.position)
+        .finish();
+    assert_eq!(built, player.ehash());
+
+    let built = HashBuilder::new()
+        .salt_of::<Player>()
+        .field(&player.name)
+        .field(&player.score)
+        .field(&player.position)
+        .finish();
+    assert_eq!(built, player.ehash());
+}
+
+#[test]
+fn test_builder_matches_derive_in_other_formats() {
+    let player = player();
+    let built = HashBuilder::<V2>::default()
+        .salt_of::<Player>()
+        .field(&player.name)
+        .field(&player.score)
+        .field(&player.position)
+        .finish();
+    assert_eq!(built, player.ehash_in::<V2>());
+    assert_ne!(built, player.ehash());
+}
+
+#[test]
+fn test_words_are_hashed_as_is() {
+    let salt = type_salt::<Player>();
+    let built = HashBuilder::new()
+        .salt(salt)
+        .words(&[1, 2])
+        .words(&[3])
+        .finish();
+    assert_eq!(built, calc_fletcher64(&[salt, 1, 2, 3]));
+}
+
+#[test]
+fn test_ehash_macro() {
+    let tick = 7u64;
+    let name = String::from("ada");
+    let position = (-1i32, 2i32);
+
+    let expected = HashBuilder::new()
+        .field(&tick)
+        .field(&name)
+        .field(&position)
+        .finish();
+    assert_eq!(ehash!(tick, name, position), expected);
+    assert_eq!(ehash!(tick, name, position,), expected);
+    assert_ne!(ehash!(name, tick, position), expected);
+
+    // The values are borrowed, not moved.
+    assert_eq!(name, "ada");
+}
+
+#[test]
+fn test_ehash_macro_of_one_value() {
+    let hash = 7u32.ehash();
+    let parts = split_u64(hash);
+    // Field hashes are added as native-endian halves, like in derived impls.
+    let expected = if cfg!(target_endian = "little") {
+        calc_fletcher64(&[parts[1], parts[0]])
+    } else {
+        calc_fletcher64(&parts)
+    };
+    assert_eq!(ehash!(7u32), expected);
+}
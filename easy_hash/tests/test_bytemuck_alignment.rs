@@ -43,3 +43,18 @@ fn test_u8_array_tail_padding() {
 
     assert_eq!(data.ehash(), expected);
 }
+
+#[test]
+fn test_odd_sized_element_packs_into_words() {
+    // A single 5-byte element can never form an aligned `u32` body, so all of
+    // its bytes take the unaligned path, whatever its address.
+    let data = [[1u8, 2, 3, 4, 5]];
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[type_salt::<[[u8; 5]; 1]>()]);
+    checksum.update(&[
+        u32::from_le_bytes([1, 2, 3, 4]),
+        u32::from_le_bytes([5, 0, 0, 0]),
+    ]);
+
+    assert_eq!(data.ehash(), checksum.value());
+}
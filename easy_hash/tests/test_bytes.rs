@@ -0,0 +1,90 @@
+use bytemuck::{Pod, Zeroable};
+use easy_hash::bytemuck_slices::ehash_pod_slice;
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, type_salt};
+
+#[derive(EasyHash, Clone, Copy)]
+#[easy_hash(bytes)]
+#[repr(C)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+unsafe impl Zeroable for Bits {}
+unsafe impl Pod for Bits {}
+
+#[derive(EasyHash, Clone, Copy)]
+#[easy_hash(bytes)]
+#[repr(C)]
+struct Particle {
+    position: [f32; 2],
+    id: u32,
+}
+
+unsafe impl Zeroable for Particle {}
+unsafe impl Pod for Particle {}
+
+#[derive(EasyHash, Clone, Copy)]
+#[easy_hash(bytes)]
+#[repr(C)]
+struct Rgb {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+unsafe impl Zeroable for Rgb {}
+unsafe impl Pod for Rgb {}
+
+#[derive(EasyHash, Clone, Copy)]
+#[easy_hash(bytes)]
+#[repr(transparent)]
+struct Cell<T: Pod>(T);
+
+unsafe impl<T: Pod> Zeroable for Cell<T> {}
+unsafe impl<T: Pod> Pod for Cell<T> {}
+
+#[test]
+fn test_union_hashes_its_bytes() {
+    let int = Bits {
+        int: 1.0f32.to_bits(),
+    };
+    let float = Bits { float: 1.0 };
+    assert_eq!(int.ehash(), float.ehash());
+    assert_ne!(int.ehash(), Bits { float: 2.0 }.ehash());
+}
+
+#[test]
+fn test_struct_matches_pod_slice_path() {
+    let particle = Particle {
+        position: [1.0, -2.0],
+        id: 7,
+    };
+    let words = [1.0f32.to_bits(), (-2.0f32).to_bits(), 7];
+
+    assert_eq!(
+        particle.ehash(),
+        ehash_pod_slice(Particle::TYPE_SALT, &words)
+    );
+    assert_eq!(Particle::TYPE_SALT, type_salt::<Particle>());
+}
+
+#[test]
+fn test_struct_with_odd_size() {
+    let a = Rgb { r: 1, g: 2, b: 3 };
+    let b = Rgb { r: 1, g: 2, b: 4 };
+
+    // A lone 3-byte value is never split around an aligned body, so its bytes are
+    // always packed into a single zero-padded word.
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[Rgb::TYPE_SALT, u32::from_le_bytes([1, 2, 3, 0])]);
+    assert_eq!(a.ehash(), checksum.value());
+    assert_ne!(a.ehash(), b.ehash());
+}
+
+#[test]
+fn test_generic_bytes_struct() {
+    assert_eq!(Cell(5u32).ehash(), Cell(5u32).ehash());
+    assert_ne!(Cell(5u32).ehash(), Cell(5i32).ehash());
+}
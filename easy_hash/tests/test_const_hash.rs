@@ -0,0 +1,141 @@
+use easy_hash::const_hash::{ConstFletcher64, ConstHash, calc_fletcher64};
+use easy_hash::fletcher::{self, Fletcher64};
+use easy_hash::{EasyHash, const_ehash};
+use proptest::prelude::*;
+
+const SPAWN: u64 = const_ehash!(("spawn", 3u32));
+const FLAGS: [u64; 2] = [const_ehash!(true), const_ehash!(false)];
+const EMPTY: u64 = const_ehash!("");
+const ONE: u64 = const_ehash!((7u8,));
+const WIDE: u64 = const_ehash!((
+    1u8,
+    2u16,
+    3u32,
+    4u64,
+    5usize,
+    -1i8,
+    -2i16,
+    -3i32,
+    -4i64,
+    -5isize,
+    "six",
+    [7u32, 8],
+    true
+));
+
+/// Keeps byte arrays 4-byte aligned, which is what `const_ehash!` assumes.
+#[repr(C, align(4))]
+struct Aligned<T>(T);
+
+#[test]
+fn test_constants_equal_runtime_hashes() {
+    assert_eq!(SPAWN, ("spawn", 3u32).ehash());
+    assert_eq!(FLAGS, [true.ehash(), false.ehash()]);
+    assert_eq!(EMPTY, "".ehash());
+    assert_eq!(ONE, (7u8,).ehash());
+    assert_eq!(
+        WIDE,
+        (
+            1u8,
+            2u16,
+            3u32,
+            4u64,
+            5usize,
+            -1i8,
+            -2i16,
+            -3i32,
+            -4i64,
+            -5isize,
+            "six",
+            [7u32, 8],
+            true
+        )
+            .ehash()
+    );
+}
+
+#[test]
+fn test_all_ones_branches() {
+    assert_eq!(const_ehash!(u8::MAX), u8::MAX.ehash());
+    assert_eq!(const_ehash!(u16::MAX), u16::MAX.ehash());
+    assert_eq!(const_ehash!(u32::MAX), u32::MAX.ehash());
+    assert_eq!(const_ehash!(u64::MAX), u64::MAX.ehash());
+    assert_eq!(const_ehash!(usize::MAX), usize::MAX.ehash());
+    assert_eq!(const_ehash!(-1i8), (-1i8).ehash());
+    assert_eq!(const_ehash!(-1i16), (-1i16).ehash());
+    assert_eq!(const_ehash!(-1i32), (-1i32).ehash());
+    assert_eq!(const_ehash!(-1i64), (-1i64).ehash());
+    assert_eq!(const_ehash!(-1isize), (-1isize).ehash());
+}
+
+#[test]
+fn test_arrays() {
+    assert_eq!(const_ehash!([1u32, 2, 3]), [1u32, 2, 3].ehash());
+    assert_eq!(const_ehash!([u64::MAX, 0]), [u64::MAX, 0].ehash());
+    assert_eq!(const_ehash!([-1i64; 3]), [-1i64; 3].ehash());
+    assert_eq!(const_ehash!([0u32; 0]), [0u32; 0].ehash());
+
+    let bytes = Aligned([1u8, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(const_ehash!([1u8, 2, 3, 4, 5, 6, 7]), bytes.0.ehash());
+    let halves = Aligned([1u16, 2, 3]);
+    assert_eq!(const_ehash!([1u16, 2, 3]), halves.0.ehash());
+    let signed = Aligned([-1i8, 0]);
+    assert_eq!(const_ehash!([-1i8, 0]), signed.0.ehash());
+}
+
+#[test]
+fn test_fletcher_matches_across_chunks() {
+    let data: Vec<u32> = (0..200_000u32)
+        .map(|i| i.wrapping_mul(0x9e37_79b9))
+        .collect();
+
+    let mut expected = Fletcher64::new();
+    expected.update(&data[..1]);
+    expected.update(&data[1..]);
+    let mut checksum = ConstFletcher64::new();
+    checksum.update(&data[..1]);
+    checksum.update(&data[1..]);
+    assert_eq!(checksum.value(), expected.value());
+
+    assert_eq!(calc_fletcher64(&[]), fletcher::calc_fletcher64(&[]));
+    assert_eq!(
+        calc_fletcher64(&[u32::MAX; 5]),
+        fletcher::calc_fletcher64(&[u32::MAX; 5])
+    );
+}
+
+#[test]
+fn test_zero_sums_match() {
+    // The salt and the word add up to `2^32 - 1`, which `Fletcher64` reduces to 0.
+    let word = u32::MAX - u32::TYPE_SALT;
+    assert_eq!(ConstHash(word).ehash(), word.ehash());
+    assert_eq!(word.ehash() & 0xffff_ffff, 0);
+    assert_eq!(
+        calc_fletcher64(&[1, u32::MAX - 1]),
+        fletcher::calc_fletcher64(&[1, u32::MAX - 1])
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_integers_match(a: u8, b: i16, c: u32, d: i64, e: usize) {
+        prop_assert_eq!(ConstHash(a).ehash(), a.ehash());
+        prop_assert_eq!(ConstHash(b).ehash(), b.ehash());
+        prop_assert_eq!(ConstHash(c).ehash(), c.ehash());
+        prop_assert_eq!(ConstHash(d).ehash(), d.ehash());
+        prop_assert_eq!(ConstHash(e).ehash(), e.ehash());
+        prop_assert_eq!(const_ehash!((a, b, c, d, e)), (a, b, c, d, e).ehash());
+    }
+
+    #[test]
+    fn prop_strings_match(s in ".{0,40}") {
+        prop_assert_eq!(ConstHash(s.as_str()).ehash(), s.as_str().ehash());
+    }
+
+    #[test]
+    fn prop_arrays_match(words: [u32; 5], bytes: [u8; 9]) {
+        prop_assert_eq!(ConstHash(words).ehash(), words.ehash());
+        let bytes = Aligned(bytes);
+        prop_assert_eq!(ConstHash(bytes.0).ehash(), bytes.0.ehash());
+    }
+}
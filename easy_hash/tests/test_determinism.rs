@@ -0,0 +1,114 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use easy_hash::EasyHash;
+use easy_hash::determinism::{assert_deterministic, check};
+
+#[derive(EasyHash)]
+struct Particle {
+    position: (i64, i64),
+    velocity: (i64, i64),
+}
+
+#[derive(EasyHash)]
+struct World {
+    tick: u64,
+    particles: Vec<Particle>,
+    spawned: u32,
+}
+
+fn setup() -> World {
+    World {
+        tick: 0,
+        particles: (0..8)
+            .map(|i| Particle {
+                position: (i, -i),
+                velocity: (1, i % 3),
+            })
+            .collect(),
+        spawned: 0,
+    }
+}
+
+fn step(world: &mut World) {
+    world.tick += 1;
+    for p in &mut world.particles {
+        p.position.0 += p.velocity.0;
+        p.position.1 += p.velocity.1;
+    }
+}
+
+#[test]
+fn test_deterministic_simulation_passes() {
+    assert!(check(setup, step, 200).is_ok());
+    assert_deterministic(setup, step, 10);
+}
+
+#[test]
+fn test_reports_first_divergent_frame() {
+    // Shared between the two instances, so whichever gets there second sees a
+    // different value.
+    let counter = AtomicU32::new(0);
+    let step = |world: &mut World| {
+        step(world);
+        if world.tick == 5 {
+            world.spawned = counter.fetch_add(1, Ordering::SeqCst);
+        }
+    };
+
+    let divergence = check(setup, step, 20).unwrap_err();
+    assert_eq!(divergence.frame, 5);
+    assert_ne!(divergence.left, divergence.right);
+    assert!(
+        divergence
+            .to_string()
+            .starts_with("state diverged at frame 5: 0x"),
+        "{divergence}"
+    );
+
+    #[cfg(feature = "tree")]
+    assert_eq!(divergence.diff.paths().collect::<Vec<_>>(), ["spawned"]);
+}
+
+#[test]
+fn test_divergent_setup_is_frame_zero() {
+    let counter = AtomicU32::new(0);
+    let setup = || World {
+        spawned: counter.fetch_add(1, Ordering::SeqCst),
+        ..setup()
+    };
+
+    assert_eq!(check(setup, step, 3).unwrap_err().frame, 0);
+}
+
+#[test]
+#[should_panic(expected = "state diverged at frame 2")]
+fn test_assert_deterministic_panics() {
+    let counter = AtomicU32::new(0);
+    assert_deterministic(
+        setup,
+        |world: &mut World| {
+            step(world);
+            if world.tick == 2 {
+                world.spawned = counter.fetch_add(1, Ordering::SeqCst);
+            }
+        },
+        5,
+    );
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn test_panicking_step_propagates() {
+    let _ = check(
+        setup,
+        |world: &mut World| {
+            step(world);
+            if world.tick == 3 {
+                panic!("boom");
+            }
+        },
+        5,
+    );
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "tree")]
+
+use easy_hash::{EasyHash, diff};
+
+#[derive(EasyHash, Clone)]
+struct Velocity {
+    x: i32,
+    y: i32,
+}
+
+#[derive(EasyHash, Clone)]
+#[allow(dead_code)]
+enum Stance {
+    Standing,
+    Crouching { depth: u8 },
+}
+
+#[derive(EasyHash, Clone)]
+struct Player {
+    velocity: Velocity,
+    stance: Stance,
+    target: Option<u32>,
+}
+
+#[derive(EasyHash, Clone)]
+struct World {
+    tick: u64,
+    players: Vec<Player>,
+}
+
+fn world() -> World {
+    let player = Player {
+        velocity: Velocity { x: 1, y: 2 },
+        stance: Stance::Standing,
+        target: None,
+    };
+    World {
+        tick: 10,
+        players: vec![player; 5],
+    }
+}
+
+fn paths(a: &World, b: &World) -> Vec<String> {
+    diff(a, b).paths().map(str::to_string).collect()
+}
+
+#[test]
+fn test_equal_values_have_no_differences() {
+    let d = diff(&world(), &world());
+    assert!(d.is_empty());
+    assert_eq!(d.to_string(), "no differences\n");
+}
+
+#[test]
+fn test_diff_finds_deepest_paths() {
+    let a = world();
+    let mut b = world();
+    b.players[3].velocity.y = 5;
+    b.tick = 11;
+
+    assert_eq!(paths(&a, &b), ["tick", "players[3].velocity.y"]);
+}
+
+#[test]
+fn test_diff_reports_variant_change_at_the_enum() {
+    let a = world();
+    let mut b = world();
+    b.players[0].stance = Stance::Crouching { depth: 1 };
+
+    assert_eq!(paths(&a, &b), ["players[0].stance"]);
+
+    let mut c = b.clone();
+    c.players[0].stance = Stance::Crouching { depth: 2 };
+    assert_eq!(paths(&b, &c), ["players[0].stance.Crouching.depth"]);
+}
+
+#[test]
+fn test_diff_reports_option_and_length_changes() {
+    let a = world();
+    let mut b = world();
+    b.players[1].target = Some(4);
+    b.players.pop();
+
+    let d = diff(&a, &b);
+    assert_eq!(
+        d.paths().collect::<Vec<_>>(),
+        ["players[1].target", "players[4]"]
+    );
+    let removed = &d.differences[1];
+    assert_eq!(removed.left.as_ref().unwrap().hash, a.players[4].ehash());
+    assert!(removed.right.is_none());
+}
+
+#[test]
+fn test_diff_display() {
+    let a = world();
+    let mut b = world();
+    b.players.pop();
+    b.players[2].velocity.x = 0;
+
+    let text = diff(&a, &b).to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2, "{text}");
+    assert!(
+        lines[0].starts_with("players[2].velocity.x: i32 0x"),
+        "{text}"
+    );
+    assert!(lines[0].contains(" -> i32 0x"), "{text}");
+    assert!(
+        lines[1].starts_with("players[4]: test_diff::Player 0x"),
+        "{text}"
+    );
+    assert!(lines[1].ends_with(" -> (missing)"), "{text}");
+}
+
+#[test]
+fn test_first_divergent_path() {
+    let a = world();
+    let mut b = world();
+    b.players[4].velocity.x = 9;
+    b.players[2].velocity.y = 9;
+
+    assert_eq!(diff(&a, &b).first().unwrap().path, "players[2].velocity.y");
+}
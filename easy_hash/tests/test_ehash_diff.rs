@@ -0,0 +1,135 @@
+#![cfg(feature = "record")]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use easy_hash::EasyHash;
+use easy_hash::record::HashRecorder;
+
+#[derive(EasyHash)]
+struct Player {
+    x: i32,
+    y: i32,
+}
+
+/// Writes a log of `frames` frames in which `player.y` changes from `diverge_at` on.
+fn write_log(name: &str, frames: u64, diverge_at: Option<u64>, trees: bool) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("easy_hash_ehash_diff_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+
+    let mut recorder = HashRecorder::create(&path).unwrap();
+    for frame in 0..frames {
+        let y = match diverge_at {
+            Some(at) if frame >= at => 1,
+            _ => 0,
+        };
+        let player = Player { x: frame as i32, y };
+        recorder.record(frame, "rng", frame * 3).unwrap();
+        if trees {
+            recorder.record_tree(frame, "player", &player).unwrap();
+        } else {
+            recorder.record_value(frame, "player", &player).unwrap();
+        }
+    }
+    recorder.flush().unwrap();
+    path
+}
+
+fn run(args: &[&Path], options: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ehash-diff"))
+        .args(options)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_matching_logs() {
+    let a = write_log("match_a.bin", 20, None, true);
+    let b = write_log("match_b.bin", 20, None, true);
+
+    let output = run(&[&a, &b], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "logs match\n");
+}
+
+#[test]
+fn test_reports_first_divergent_frame_and_paths() {
+    let a = write_log("paths_a.bin", 20, None, true);
+    let b = write_log("paths_b.bin", 20, Some(7), true);
+
+    let output = run(&[&a, &b], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let text = stdout(&output);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "first divergence at frame 7");
+    assert!(lines[1].starts_with("  player: 0x"), "{text}");
+    assert!(lines[2].starts_with("    y: i32 0x"), "{text}");
+    assert_eq!(lines.len(), 3, "{text}");
+}
+
+#[test]
+fn test_json_output() {
+    let a = write_log("json_a.bin", 10, None, false);
+    let b = write_log("json_b.bin", 12, None, false);
+
+    let output = run(&[&a, &b], &["--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let text = stdout(&output);
+    assert!(
+        text.starts_with(
+            r#"{"diverged":true,"frame":10,"labels":[{"label":"rng","left":null,"right":"0x"#
+        ),
+        "{text}"
+    );
+    assert!(text.ends_with("\"paths\":null}]}\n"), "{text}");
+
+    let output = run(&[&a, &a], &["--json"]);
+    assert_eq!(stdout(&output), "{\"diverged\":false}\n");
+}
+
+#[test]
+fn test_bisect_finds_first_divergent_run() {
+    let reference = write_log("bisect_ref.bin", 10, None, true);
+    let runs: Vec<PathBuf> = (0..6)
+        .map(|i| {
+            let diverge_at = (i >= 4).then_some(9 - i as u64);
+            write_log(&format!("bisect_{i}.bin"), 10, diverge_at, true)
+        })
+        .collect();
+    let mut args: Vec<&Path> = vec![&reference];
+    args.extend(runs.iter().map(PathBuf::as_path));
+
+    let output = run(&args, &["--bisect"]);
+    assert_eq!(output.status.code(), Some(1));
+    let text = stdout(&output);
+    assert!(text.starts_with("first divergent run: "), "{text}");
+    assert!(
+        text.contains("bisect_4.bin (run 5 of 6)\nfirst divergence at frame 5\n"),
+        "{text}"
+    );
+
+    let output = run(&args[..4], &["--bisect", "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "{\"diverged\":false}\n");
+}
+
+#[test]
+fn test_usage_errors() {
+    let a = write_log("usage_a.bin", 1, None, false);
+
+    assert_eq!(run(&[&a], &[]).status.code(), Some(2));
+    assert_eq!(run(&[&a, &a], &["--frobnicate"]).status.code(), Some(2));
+    assert_eq!(
+        run(&[&a, Path::new("does/not/exist.bin")], &[])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(run(&[], &["--help"]).status.code(), Some(0));
+}
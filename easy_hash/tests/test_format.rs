@@ -0,0 +1,156 @@
+#![cfg(feature = "alloc")]
+
+use easy_hash::EasyHash;
+use easy_hash::format::HashFormat;
+use easy_hash::v1::V1;
+use easy_hash::v2::V2;
+
+#[derive(EasyHash)]
+struct Inventory {
+    owner: String,
+    items: Vec<u32>,
+    #[easy_hash(quantize = 0.5)]
+    weight: f32,
+    slot: Option<(u8, i64)>,
+}
+
+#[derive(EasyHash)]
+enum Event {
+    Start,
+    Move(i32, i32),
+    Say { text: String },
+}
+
+fn inventory() -> Inventory {
+    Inventory {
+        owner: "ada".to_string(),
+        items: vec![1, 2, 3],
+        weight: 1.2,
+        slot: Some((3, -7)),
+    }
+}
+
+#[test]
+fn test_v1_is_ehash() {
+    assert_eq!(inventory().ehash_in::<V1>(), inventory().ehash());
+    assert_eq!(easy_hash::v1::ehash(&inventory()), inventory().ehash());
+    for event in [
+        Event::Start,
+        Event::Move(1, -1),
+        Event::Say {
+            text: "hi".to_string(),
+        },
+    ] {
+        assert_eq!(event.ehash_in::<V1>(), event.ehash());
+    }
+    assert_eq!(7u32.ehash_in::<V1>(), 7u32.ehash());
+    assert_eq!([1u8, 2, 3].ehash_in::<V1>(), [1u8, 2, 3].ehash());
+}
+
+#[test]
+fn test_v2_differs_from_v1() {
+    assert_ne!(inventory().ehash_in::<V2>(), inventory().ehash());
+    assert_ne!(7u32.ehash_in::<V2>(), 7u32.ehash());
+    assert_eq!(7u32.ehash_in::<V2>(), V2::finish(7u32.ehash()));
+    assert_eq!(
+        easy_hash::v2::ehash(&inventory()),
+        inventory().ehash_in::<V2>()
+    );
+}
+
+#[test]
+fn test_v2_is_deterministic() {
+    assert_eq!(inventory().ehash_in::<V2>(), inventory().ehash_in::<V2>());
+    assert_ne!(
+        Event::Move(1, 2).ehash_in::<V2>(),
+        Event::Move(2, 1).ehash_in::<V2>()
+    );
+}
+
+#[test]
+fn test_v2_prefixes_sequences_with_their_length() {
+    let a: Vec<Vec<u32>> = vec![vec![], vec![1]];
+    let b: Vec<Vec<u32>> = vec![vec![1], vec![]];
+    assert_ne!(a.ehash_in::<V2>(), b.ehash_in::<V2>());
+
+    // Empty strings are hashed as nothing but their salt in v1.
+    let a = vec!["".to_string(), "ab".to_string()];
+    let b = vec!["ab".to_string(), "".to_string()];
+    assert_ne!(a.ehash_in::<V2>(), b.ehash_in::<V2>());
+
+    // Without a length, trailing zero bytes can be lost in the padding of the last word.
+    // Both slices start at the same 4-aligned address, where v1 hashes `long` as one
+    // native-endian word and the 3 bytes of `short` padded little-endian.
+    #[repr(align(4))]
+    struct Aligned([u8; 4]);
+    let bytes = Aligned([1, 2, 0, 0]);
+    let short: &[u8] = &bytes.0[..3];
+    let long: &[u8] = &bytes.0;
+    if cfg!(target_endian = "little") {
+        assert_eq!(short.ehash_in::<V1>(), long.ehash_in::<V1>());
+    }
+    assert_ne!(short.ehash_in::<V2>(), long.ehash_in::<V2>());
+}
+
+#[test]
+fn test_v2_pod_slices_do_not_depend_on_their_address() {
+    let data: Vec<u8> = (1..=31).collect();
+    // The same bytes at every offset within a word.
+    let hashes: Vec<u64> = (0..4)
+        .map(|offset| {
+            let mut buffer = [0u8; 36];
+            buffer[offset..offset + data.len()].copy_from_slice(&data);
+            buffer[offset..offset + data.len()].ehash_in::<V2>()
+        })
+        .collect();
+    assert!(hashes.iter().all(|&hash| hash == hashes[0]), "{hashes:x?}");
+    assert_eq!(hashes[0], data.as_slice().ehash_in::<V2>());
+}
+
+#[test]
+fn test_formats_have_distinct_versions() {
+    assert_eq!(V1::VERSION, 1);
+    assert_eq!(V2::VERSION, 2);
+}
+
+/// Two types whose 32-bit salts collide.
+macro_rules! colliding_salt {
+    ($name:ident) => {
+        struct $name(u32);
+
+        impl EasyHash for $name {
+            const TYPE_SALT: u32 = 1;
+
+            fn ehash(&self) -> u64 {
+                self.ehash_in::<V1>()
+            }
+
+            fn ehash_in<F: HashFormat>(&self) -> u64 {
+                let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                checksum.update(&easy_hash::split_u64(self.0.ehash_in::<F>()));
+                F::finish(checksum.value())
+            }
+        }
+    };
+}
+
+colliding_salt!(Meters);
+colliding_salt!(Seconds);
+
+#[test]
+fn test_v2_uses_wide_salts() {
+    assert_eq!(Meters(3).ehash(), Seconds(3).ehash());
+    assert_ne!(Meters(3).ehash_in::<V2>(), Seconds(3).ehash_in::<V2>());
+    assert_eq!(Meters::TYPE_SALT64, easy_hash::type_salt64::<Meters>());
+}
+
+#[test]
+fn test_transparent_types_forward_wide_salts() {
+    #[derive(EasyHash)]
+    #[easy_hash(transparent)]
+    struct Id(u64);
+
+    assert_eq!(Id::TYPE_SALT64, u64::TYPE_SALT64);
+    assert_eq!(Id(5).ehash_in::<V2>(), 5u64.ehash_in::<V2>());
+}
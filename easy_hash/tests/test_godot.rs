@@ -1,5 +1,7 @@
+#![cfg(feature = "godot")]
+
 use easy_hash::fletcher::Fletcher64;
-use easy_hash::{EasyHash, type_salt};
+use easy_hash::{EasyHash, EasyHashQuantized, Quantization, type_salt};
 use godot::builtin::{Vector2, Vector2i, Vector3, Vector3i};
 
 #[test]
@@ -47,3 +49,17 @@ fn test_vector3i_hash_matches_manual() {
 
     assert_eq!(value.ehash(), checksum.value());
 }
+
+#[test]
+fn test_vector_quantized_hash() {
+    let q = Quantization::Ulps(4);
+    let a = Vector2::new(1.0, 2.0);
+    let b = Vector2::new(f32::from_bits(1.0f32.to_bits() + 1), 2.0);
+    assert_eq!(a.ehash_quantized(q), b.ehash_quantized(q));
+    assert_eq!(a.ehash_quantized(q), a.ehash());
+
+    let c = Vector3::new(1.0, 2.0, 3.0);
+    let d = Vector3::new(1.0, 2.0, 3.5);
+    assert_eq!(c.ehash_quantized(q), c.ehash());
+    assert_ne!(c.ehash_quantized(q), d.ehash_quantized(q));
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "alloc")]
+
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, u64_to_u32_slice};
+
+#[derive(EasyHash)]
+struct Body {
+    #[easy_hash(group = "physics")]
+    position: (i32, i32),
+    #[easy_hash(group = "physics")]
+    velocity: (i32, i32),
+    sprite: String,
+}
+
+#[derive(EasyHash)]
+struct Entity {
+    #[easy_hash(group = "physics", group = "net")]
+    body: Body,
+    #[easy_hash(group = "net")]
+    owner: u32,
+    name: String,
+}
+
+fn entity(name: &str, x: i32) -> Entity {
+    Entity {
+        body: Body {
+            position: (x, 0),
+            velocity: (1, 0),
+            sprite: "unit.png".to_string(),
+        },
+        owner: 7,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn test_group_ignores_untagged_fields() {
+    let a = entity("goblin", 3);
+    let b = entity("orc", 3);
+
+    assert_eq!(a.ehash_group("physics"), b.ehash_group("physics"));
+    assert_eq!(a.ehash_group("net"), b.ehash_group("net"));
+    assert_ne!(a.ehash(), b.ehash());
+}
+
+#[test]
+fn test_group_detects_tagged_changes() {
+    let a = entity("goblin", 3);
+    let b = entity("goblin", 4);
+
+    assert_ne!(a.ehash_group("physics"), b.ehash_group("physics"));
+    assert_ne!(a.ehash_group("net"), b.ehash_group("net"));
+}
+
+#[test]
+fn test_group_recurses_into_nested_types() {
+    let e = entity("goblin", 3);
+
+    let mut body = Fletcher64::new();
+    body.update(&[Body::TYPE_SALT]);
+    body.update(u64_to_u32_slice(&[(3, 0).ehash(), (1, 0).ehash()]));
+
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[Entity::TYPE_SALT]);
+    checksum.update(u64_to_u32_slice(&[body.value()]));
+
+    assert_eq!(e.ehash_group("physics"), checksum.value());
+}
+
+#[test]
+fn test_unnamed_group_hashes_whole() {
+    let e = entity("goblin", 3);
+
+    assert_eq!(e.ehash_group("audio"), e.ehash());
+    // `Body` names no "net" group, so all of it is part of the entity's "net" hash.
+    assert_eq!(e.body.ehash_group("net"), e.body.ehash());
+}
+
+#[test]
+fn test_types_without_groups_hash_whole() {
+    assert_eq!(5u32.ehash_group("physics"), 5u32.ehash());
+    assert_eq!(
+        String::from("x").ehash_group("physics"),
+        String::from("x").ehash()
+    );
+}
+
+#[test]
+fn test_group_recurses_through_containers() {
+    let a = vec![Some(entity("goblin", 3)), None];
+    let b = vec![Some(entity("orc", 3)), None];
+
+    assert_eq!(a.ehash_group("physics"), b.ehash_group("physics"));
+    assert_ne!(a.ehash(), b.ehash());
+}
+
+#[derive(EasyHash)]
+#[allow(dead_code)]
+enum Event {
+    Move {
+        #[easy_hash(group = "physics")]
+        dx: i32,
+        label: String,
+    },
+    Say(String),
+}
+
+#[test]
+fn test_enum_groups() {
+    let a = Event::Move {
+        dx: 1,
+        label: "a".into(),
+    };
+    let b = Event::Move {
+        dx: 1,
+        label: "b".into(),
+    };
+
+    assert_eq!(a.ehash_group("physics"), b.ehash_group("physics"));
+    assert_ne!(
+        Event::Say("a".into()).ehash_group("physics"),
+        a.ehash_group("physics")
+    );
+}
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Player(Entity);
+
+#[test]
+fn test_transparent_forwards_groups() {
+    let e = entity("goblin", 3);
+    let physics = e.ehash_group("physics");
+
+    assert_eq!(Player(e).ehash_group("physics"), physics);
+}
@@ -1,4 +1,6 @@
-use easy_hash::EasyHash;
+#![cfg(feature = "nalgebra")]
+
+use easy_hash::{EasyHash, EasyHashQuantized, Quantization};
 use nalgebra::{Isometry2, Point2, UnitVector2, Vector2, Vector3};
 
 #[test]
@@ -48,3 +50,17 @@ fn test_isometry2_hash() {
     assert_ne!(a.ehash(), c.ehash());
     assert_ne!(a.ehash(), d.ehash());
 }
+
+#[test]
+fn test_vector_quantized_hash() {
+    let q = Quantization::Step(1e-3);
+    let a = Vector2::new(1.0f32, 2.0);
+    let b = Vector2::new(1.0f32 + 1e-5, 2.0 - 1e-5);
+    assert_eq!(a.ehash_quantized(q), b.ehash_quantized(q));
+    assert_eq!(a.ehash_quantized(q), a.ehash());
+
+    let c = Vector3::new(1.0f32, 2.0, 3.0);
+    let d = Vector3::new(1.0f32, 2.0, 3.01);
+    assert_eq!(c.ehash_quantized(q), c.ehash());
+    assert_ne!(c.ehash_quantized(q), d.ehash_quantized(q));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "ordered_float")]
+
 use easy_hash::EasyHash;
 use ordered_float::{NotNan, OrderedFloat};
 
@@ -0,0 +1,137 @@
+#![cfg(feature = "rayon")]
+
+use std::collections::BTreeMap;
+
+use easy_hash::v2::V2;
+use easy_hash::{EasyHash, ParEasyHash};
+use proptest::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+#[derive(EasyHash)]
+struct Entity {
+    id: u32,
+    name: String,
+    position: (i32, i32),
+    #[easy_hash(quantize = 0.01)]
+    health: f32,
+}
+
+fn entities(len: u32) -> Vec<Entity> {
+    (0..len)
+        .map(|id| Entity {
+            id,
+            name: format!("entity {id}"),
+            position: (id as i32, -(id as i32)),
+            health: id as f32 * 0.5,
+        })
+        .collect()
+}
+
+/// Run `f` on thread pools of different sizes, returning its result on each.
+fn on_pools<R: Send>(f: impl Fn() -> R + Sync) -> Vec<R> {
+    [1, 2, 3, 8]
+        .into_iter()
+        .map(|threads| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(&f)
+        })
+        .collect()
+}
+
+#[test]
+fn test_vec_matches_sequential() {
+    for len in [0, 1, 4095, 4096, 4097, 50_000] {
+        let entities = entities(len);
+        for hash in on_pools(|| entities.par_ehash()) {
+            assert_eq!(hash, entities.ehash(), "len {len}");
+        }
+        for hash in on_pools(|| entities.par_ehash_in::<V2>()) {
+            assert_eq!(hash, entities.ehash_in::<V2>(), "len {len}");
+        }
+    }
+}
+
+#[test]
+fn test_nested_vec_matches_sequential() {
+    let nested: Vec<Vec<u64>> = (0..2_000u64).map(|i| (0..i % 50).collect()).collect();
+    for hash in on_pools(|| nested.par_ehash()) {
+        assert_eq!(hash, nested.ehash());
+    }
+}
+
+#[test]
+fn test_btree_map_matches_sequential() {
+    for len in [0, 1, 4096, 4097, 20_000] {
+        let map: BTreeMap<u32, Entity> = entities(len)
+            .into_iter()
+            .map(|entity| (entity.id.wrapping_mul(0x9e37_79b9), entity))
+            .collect();
+        for hash in on_pools(|| map.par_ehash()) {
+            assert_eq!(hash, map.ehash(), "len {len}");
+        }
+        for hash in on_pools(|| map.par_ehash_in::<V2>()) {
+            assert_eq!(hash, map.ehash_in::<V2>(), "len {len}");
+        }
+    }
+}
+
+#[test]
+fn test_zero_sums_match_sequential() {
+    // The salt and the word add up to `2^32 - 1`, which `Fletcher64` reduces to 0.
+    let slice: &[u32] = &[u32::MAX - <[u32] as EasyHash>::TYPE_SALT];
+    assert_eq!(slice.ehash() & 0xffff_ffff, 0);
+    for hash in on_pools(|| slice.par_ehash()) {
+        assert_eq!(hash, slice.ehash());
+    }
+    let array = [u32::MAX - <[u32; 1] as EasyHash>::TYPE_SALT];
+    assert_eq!(array.ehash() & 0xffff_ffff, 0);
+    for hash in on_pools(|| array.par_ehash()) {
+        assert_eq!(hash, array.ehash());
+    }
+}
+
+#[test]
+fn test_pod_slice_matches_sequential() {
+    // Longer than both a task's chunk and the stretch `Fletcher64` sums between
+    // reductions.
+    let data: Vec<u8> = (0..1_000_003u32).map(|i| (i * 7 + i / 251) as u8).collect();
+    // Offsets give slices with every alignment, so v1 hashes an unaligned head.
+    for offset in 0..4 {
+        let slice = &data[offset..];
+        for hash in on_pools(|| slice.par_ehash()) {
+            assert_eq!(hash, slice.ehash(), "offset {offset}");
+        }
+        for hash in on_pools(|| slice.par_ehash_in::<V2>()) {
+            assert_eq!(hash, slice.ehash_in::<V2>(), "offset {offset}");
+        }
+    }
+
+    let words: Vec<u32> = (0..300_000)
+        .map(|i: u32| i.wrapping_mul(0x9e37_79b9))
+        .collect();
+    for hash in on_pools(|| words.as_slice().par_ehash()) {
+        assert_eq!(hash, words.as_slice().ehash());
+    }
+}
+
+#[test]
+fn test_pod_array_matches_sequential() {
+    let array = Box::new([u32::MAX; 100_000]);
+    for hash in on_pools(|| array.par_ehash()) {
+        assert_eq!(hash, array.ehash());
+    }
+    for hash in on_pools(|| array.par_ehash_in::<V2>()) {
+        assert_eq!(hash, array.ehash_in::<V2>());
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_vec_matches_sequential(values in prop::collection::vec(any::<u64>(), 0..10_000)) {
+        prop_assert_eq!(values.par_ehash(), values.ehash());
+        prop_assert_eq!(values.as_slice().par_ehash(), values.as_slice().ehash());
+    }
+}
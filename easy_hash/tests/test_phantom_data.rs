@@ -245,6 +245,7 @@ fn test_phantom_data_with_custom_type() {
     assert_eq!(s1.ehash(), s2.ehash());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_phantom_data_with_vec_type() {
     let s1 = WithComplexPhantomData::<Vec<u32>> {
@@ -258,6 +259,7 @@ fn test_phantom_data_with_vec_type() {
     assert_eq!(s1.ehash(), s2.ehash());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_phantom_data_different_vec_element_types() {
     let s1 = WithComplexPhantomData::<Vec<u32>> {
@@ -9,6 +9,7 @@ fn manual_option_some_hash(value: u64) -> u64 {
     checksum.value()
 }
 
+#[cfg(feature = "alloc")]
 fn manual_vec_hash(values: &[u64]) -> u64 {
     let mut checksum = Fletcher64::new();
     checksum.update(&[type_salt::<Vec<u64>>()]);
@@ -74,6 +75,8 @@ proptest! {
         prop_assert_eq!(Some(value).ehash(), manual_option_some_hash(value));
     }
 
+    // Without `alloc`, `Vec` derefs to the slice impl, which hashes differently.
+    #[cfg(feature = "alloc")]
     #[test]
     fn prop_vec_hash_matches_manual(values in prop::collection::vec(any::<u64>(), 0..64)) {
         prop_assert_eq!(values.ehash(), manual_vec_hash(&values));
@@ -0,0 +1,97 @@
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, EasyHashQuantized, Quantization, u64_to_u32_slice};
+
+#[derive(EasyHash)]
+struct Body {
+    id: u32,
+    #[easy_hash(quantize = 1e-3)]
+    x: f32,
+    #[easy_hash(ulps = 4)]
+    y: f64,
+}
+
+#[test]
+fn test_quantized_fields_tolerate_small_errors() {
+    let a = Body {
+        id: 1,
+        x: 0.1,
+        y: 1.0,
+    };
+    let b = Body {
+        id: 1,
+        x: 0.1 + 1e-6,
+        y: f64::from_bits(1.0f64.to_bits() + 1),
+    };
+
+    assert_eq!(a.ehash(), b.ehash());
+}
+
+#[test]
+fn test_quantized_fields_detect_large_errors() {
+    let a = Body {
+        id: 1,
+        x: 0.1,
+        y: 1.0,
+    };
+    let b = Body {
+        id: 1,
+        x: 0.2,
+        y: 1.0,
+    };
+
+    assert_ne!(a.ehash(), b.ehash());
+}
+
+#[test]
+fn test_quantized_field_hashes_as_snapped_value() {
+    let body = Body {
+        id: 7,
+        x: 0.5004,
+        y: 2.0,
+    };
+
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[Body::TYPE_SALT]);
+    checksum.update(u64_to_u32_slice(&[
+        7u32.ehash(),
+        0.5f32.ehash(),
+        2.0f64.ehash(),
+    ]));
+
+    assert_eq!(body.ehash(), checksum.value());
+}
+
+#[test]
+fn test_negative_zero_hashes_as_zero() {
+    let q = Quantization::Step(0.1);
+    assert_eq!((-0.01f32).ehash_quantized(q), 0.0f32.ehash());
+    assert_eq!(
+        (-0.0f64).ehash_quantized(Quantization::Ulps(1)),
+        0.0f64.ehash()
+    );
+}
+
+#[test]
+fn test_quantized_containers() {
+    let q = Quantization::Step(0.25);
+    assert_eq!(Some(0.26f32).ehash_quantized(q), Some(0.25f32).ehash());
+    assert_eq!(None::<f32>.ehash_quantized(q), None::<f32>.ehash());
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        vec![1.01f64, 1.99].ehash_quantized(q),
+        vec![1.0f64, 2.0].ehash()
+    );
+}
+
+#[derive(EasyHash)]
+struct Meters(#[easy_hash(quantize = 0.01)] f64);
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Seconds(#[easy_hash(quantize = 0.01)] f64);
+
+#[test]
+fn test_quantized_tuple_and_transparent_fields() {
+    assert_eq!(Meters(1.001).ehash(), Meters(0.999).ehash());
+    assert_eq!(Seconds(1.001).ehash(), 1.0f64.ehash());
+}
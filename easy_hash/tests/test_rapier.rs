@@ -1,3 +1,5 @@
+#![cfg(feature = "rapier")]
+
 use easy_hash::EasyHash;
 use rapier2d::prelude::{
     ColliderHandle, ImpulseJointHandle, MultibodyJointHandle, RigidBodyHandle,
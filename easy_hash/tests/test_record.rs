@@ -0,0 +1,146 @@
+#![cfg(feature = "record")]
+
+use easy_hash::EasyHash;
+use easy_hash::record::{
+    FORMAT_VERSION, HashRecorder, MAGIC, MAX_TREE_DEPTH, Record, RecordReader,
+};
+
+#[derive(EasyHash)]
+struct Body {
+    position: (i32, i32),
+    name: String,
+}
+
+fn body(x: i32) -> Body {
+    Body {
+        position: (x, 0),
+        name: "crate".to_string(),
+    }
+}
+
+#[test]
+fn test_records_round_trip() {
+    let mut recorder = HashRecorder::new(Vec::new()).unwrap();
+    recorder.record(0, "rng", 42).unwrap();
+    recorder.record_value(1, "body", &body(1)).unwrap();
+    recorder.record_tree(2, "body", &body(2)).unwrap();
+    let bytes = recorder.into_inner();
+
+    let reader = RecordReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.version(), FORMAT_VERSION);
+    let records: Vec<Record> = reader.collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0],
+        Record {
+            frame: 0,
+            label: "rng".to_string(),
+            hash: 42,
+            tree: None,
+        }
+    );
+    assert_eq!(records[1].hash, body(1).ehash());
+    assert_eq!(records[1].tree, None);
+    assert_eq!(records[2].frame, 2);
+    assert_eq!(records[2].hash, body(2).ehash());
+    assert_eq!(records[2].tree.as_ref(), Some(&body(2).ehash_tree()));
+}
+
+#[test]
+fn test_format_is_little_endian() {
+    let mut recorder = HashRecorder::new(Vec::new()).unwrap();
+    recorder.record(0x0102, "ab", 0x0a0b0c0d).unwrap();
+    let bytes = recorder.into_inner();
+
+    let mut expected = MAGIC.to_vec();
+    expected.extend([1, 0, 0, 0]);
+    expected.extend([0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+    expected.extend([2, 0, 0, 0, b'a', b'b']);
+    expected.extend([0x0d, 0x0c, 0x0b, 0x0a, 0, 0, 0, 0]);
+    expected.push(0);
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn test_rejects_other_files() {
+    assert!(RecordReader::new(&b"NOTALOG!\x01\x00\x00\x00"[..]).is_err());
+
+    let mut future = MAGIC.to_vec();
+    future.extend((FORMAT_VERSION + 1).to_le_bytes());
+    assert!(RecordReader::new(future.as_slice()).is_err());
+}
+
+#[test]
+fn test_truncated_log_is_an_error() {
+    let mut recorder = HashRecorder::new(Vec::new()).unwrap();
+    recorder.record(0, "a", 1).unwrap();
+    recorder.record(1, "b", 2).unwrap();
+    let mut bytes = recorder.into_inner();
+    bytes.truncate(bytes.len() - 3);
+
+    let mut reader = RecordReader::new(bytes.as_slice()).unwrap();
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+/// A log holding one record whose tree is a chain of `depth` nodes.
+fn log_with_tree_depth(depth: usize) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    bytes.extend(0u64.to_le_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(0u64.to_le_bytes());
+    bytes.push(1);
+    for level in 1..=depth {
+        // Empty name and type name, salt, hash, and the number of children.
+        bytes.extend([0; 8]);
+        bytes.extend([0; 12]);
+        bytes.extend(u32::from(level < depth).to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn test_deeply_nested_tree_is_an_error() {
+    let bytes = log_with_tree_depth(MAX_TREE_DEPTH);
+    let record = RecordReader::new(bytes.as_slice())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let depth = record.tree.unwrap().iter().count();
+    assert_eq!(depth, MAX_TREE_DEPTH);
+
+    for depth in [MAX_TREE_DEPTH + 1, 1_000_000] {
+        let bytes = log_with_tree_depth(depth);
+        let mut reader = RecordReader::new(bytes.as_slice()).unwrap();
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "depth {depth}");
+        assert!(reader.next().is_none());
+    }
+}
+
+#[test]
+fn test_record_file() {
+    let path = std::env::temp_dir().join(format!("easy_hash_record_{}.bin", std::process::id()));
+    {
+        let mut recorder = HashRecorder::create(&path).unwrap();
+        for frame in 0..100 {
+            recorder
+                .record_value(frame, "body", &body(frame as i32))
+                .unwrap();
+        }
+        recorder.flush().unwrap();
+    }
+
+    let hashes: Vec<u64> = RecordReader::open(&path)
+        .unwrap()
+        .map(|record| record.unwrap().hash)
+        .collect();
+    std::fs::remove_file(&path).unwrap();
+
+    let expected: Vec<u64> = (0..100).map(|x| body(x).ehash()).collect();
+    assert_eq!(hashes, expected);
+}
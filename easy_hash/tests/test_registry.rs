@@ -0,0 +1,144 @@
+#![cfg(feature = "registry")]
+
+use std::any::type_name;
+
+use easy_hash::EasyHash;
+use easy_hash::registry::{Salt, SaltCollision, SaltEntry, SaltRegistry, assert_no_collisions};
+
+#[derive(EasyHash)]
+struct Player {
+    name: String,
+    score: u32,
+}
+
+#[derive(EasyHash)]
+#[allow(dead_code)]
+enum Command {
+    Stop,
+    Go(u32),
+}
+
+#[derive(EasyHash)]
+struct Wrapper<T> {
+    value: T,
+}
+
+easy_hash::register_salts!(Wrapper<u8>);
+
+#[allow(dead_code)]
+mod foreign {
+    pub struct Velocity {
+        pub x: f32,
+    }
+}
+
+#[derive(EasyHash)]
+#[easy_hash(remote = "foreign::Velocity")]
+#[allow(dead_code)]
+struct VelocityDef {
+    x: f32,
+}
+
+#[test]
+fn test_derived_types_are_registered() {
+    let registry = SaltRegistry::global();
+    assert_eq!(
+        registry.name(Player::TYPE_SALT),
+        Some(type_name::<Player>())
+    );
+    assert_eq!(
+        registry.name64(Command::TYPE_SALT64),
+        Some(type_name::<Command>())
+    );
+    assert_eq!(
+        registry.name(VelocityDef::TYPE_SALT),
+        Some(type_name::<foreign::Velocity>())
+    );
+}
+
+#[test]
+fn test_builtin_and_explicitly_registered_types() {
+    let registry = SaltRegistry::global();
+    assert_eq!(registry.name(u32::TYPE_SALT), Some("u32"));
+    assert_eq!(
+        registry.name(String::TYPE_SALT),
+        Some(type_name::<String>())
+    );
+    assert_eq!(
+        registry.name(Wrapper::<u8>::TYPE_SALT),
+        Some(type_name::<Wrapper<u8>>())
+    );
+    assert_eq!(registry.name(Wrapper::<u16>::TYPE_SALT), None);
+}
+
+#[test]
+fn test_no_collisions_in_this_binary() {
+    assert_no_collisions();
+}
+
+#[test]
+fn test_known_collisions_are_still_listed() {
+    let collisions = SaltRegistry::global().collisions();
+    assert!(collisions.contains(&SaltCollision {
+        salt: Salt::Narrow(f32::TYPE_SALT),
+        type_names: vec!["f32", "f64"],
+    }));
+    assert_ne!(f32::TYPE_SALT64, f64::TYPE_SALT64);
+}
+
+#[test]
+fn test_collisions_are_reported() {
+    let mut registry = SaltRegistry::new();
+    registry.insert(&SaltEntry {
+        type_name: "a::Meters",
+        salt: 7,
+        salt64: 1,
+    });
+    registry.insert(&SaltEntry {
+        type_name: "b::Seconds",
+        salt: 7,
+        salt64: 2,
+    });
+    // Registering a type twice is not a collision.
+    registry.insert(&SaltEntry {
+        type_name: "b::Seconds",
+        salt: 7,
+        salt64: 2,
+    });
+
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.name(7), None);
+    assert_eq!(
+        registry.names(7).collect::<Vec<_>>(),
+        ["a::Meters", "b::Seconds"]
+    );
+    assert_eq!(registry.name64(2), Some("b::Seconds"));
+
+    let collisions = registry.collisions();
+    assert_eq!(
+        collisions,
+        [SaltCollision {
+            salt: Salt::Narrow(7),
+            type_names: vec!["a::Meters", "b::Seconds"],
+        }]
+    );
+    assert_eq!(
+        collisions[0].to_string(),
+        "TYPE_SALT 0x00000007 is shared by a::Meters, b::Seconds"
+    );
+}
+
+#[cfg(feature = "tree")]
+#[test]
+fn test_tree_nodes_resolve_their_salts() {
+    let tree = Player {
+        name: "ada".to_string(),
+        score: 3,
+    }
+    .ehash_tree();
+    assert_eq!(tree.registered_type_name(), Some(type_name::<Player>()));
+    assert_eq!(
+        tree.get("score").unwrap().registered_type_name(),
+        Some("u32")
+    );
+}
@@ -0,0 +1,109 @@
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, type_salt, u64_to_u32_slice};
+
+/// Stand-in for a foreign crate whose types don't implement `EasyHash`.
+mod foreign {
+    pub struct Vec2 {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    pub struct Handle(pub u32, pub u32);
+
+    pub enum Shape<T> {
+        Circle(T),
+        Rect { w: T, h: T },
+    }
+}
+
+#[derive(EasyHash)]
+#[easy_hash(remote = "foreign::Vec2")]
+#[allow(dead_code)]
+struct Vec2Def {
+    x: f32,
+    y: f32,
+}
+
+#[derive(EasyHash)]
+#[easy_hash(remote = "foreign::Handle")]
+#[allow(dead_code)]
+struct HandleDef(u32, u32);
+
+#[derive(EasyHash)]
+#[easy_hash(remote = "foreign::Shape<T>")]
+#[allow(dead_code)]
+enum ShapeDef<T> {
+    Circle(T),
+    Rect { w: T, h: T },
+}
+
+#[derive(EasyHash)]
+struct Body {
+    #[easy_hash(with = "Vec2Def")]
+    position: foreign::Vec2,
+    #[easy_hash(with = "HandleDef")]
+    handle: foreign::Handle,
+    mass: f32,
+}
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Velocity(#[easy_hash(with = "Vec2Def")] foreign::Vec2);
+
+#[test]
+fn test_remote_struct_hashes_like_a_derive() {
+    let v = foreign::Vec2 { x: 1.0, y: -2.0 };
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[type_salt::<foreign::Vec2>()]);
+    checksum.update(u64_to_u32_slice(&[1.0f32.ehash(), (-2.0f32).ehash()]));
+
+    assert_eq!(Vec2Def::ehash(&v), checksum.value());
+    assert_eq!(Vec2Def::TYPE_SALT, type_salt::<foreign::Vec2>());
+}
+
+#[test]
+fn test_remote_tuple_struct() {
+    let a = foreign::Handle(1, 2);
+    let b = foreign::Handle(2, 1);
+    assert_ne!(HandleDef::ehash(&a), HandleDef::ehash(&b));
+}
+
+#[test]
+fn test_remote_generic_enum() {
+    let circle = foreign::Shape::Circle(1.0f32);
+    let rect = foreign::Shape::Rect { w: 1.0f32, h: 2.0 };
+    let wide = foreign::Shape::Rect { w: 2.0f32, h: 1.0 };
+
+    assert_ne!(ShapeDef::ehash(&circle), ShapeDef::ehash(&rect));
+    assert_ne!(ShapeDef::ehash(&rect), ShapeDef::ehash(&wide));
+    assert_ne!(
+        ShapeDef::ehash(&foreign::Shape::Circle(1u32)),
+        ShapeDef::ehash(&circle)
+    );
+}
+
+#[test]
+fn test_with_field_uses_remote_definition() {
+    let body = Body {
+        position: foreign::Vec2 { x: 3.0, y: 4.0 },
+        handle: foreign::Handle(5, 6),
+        mass: 2.5,
+    };
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[Body::TYPE_SALT]);
+    checksum.update(u64_to_u32_slice(&[
+        Vec2Def::ehash(&body.position),
+        HandleDef::ehash(&body.handle),
+        2.5f32.ehash(),
+    ]));
+
+    assert_eq!(body.ehash(), checksum.value());
+}
+
+#[test]
+fn test_transparent_with_remote_field() {
+    let velocity = Velocity(foreign::Vec2 { x: 0.5, y: 0.25 });
+
+    assert_eq!(velocity.ehash(), Vec2Def::ehash(&velocity.0));
+    assert_eq!(Velocity::TYPE_SALT, Vec2Def::TYPE_SALT);
+}
@@ -0,0 +1,288 @@
+#![cfg(feature = "alloc")]
+#![allow(dead_code)]
+
+use easy_hash::EasyHash;
+use easy_hash::schema::{SchemaMismatch, check};
+
+/// Successive versions of the same types, as a save format evolves.
+mod v1 {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub level: u32,
+        pub items: Vec<Item>,
+    }
+
+    #[derive(EasyHash)]
+    pub enum Event {
+        Start,
+        Move { x: i32, y: i32 },
+    }
+}
+
+/// The same definitions as `v1`, in another module.
+mod moved {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub level: u32,
+        pub items: Vec<Item>,
+    }
+}
+
+/// `Item.id` is retyped, which only shows in `Save` through its field's schema.
+mod retyped_nested {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u64,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub level: u32,
+        pub items: Vec<Item>,
+    }
+}
+
+mod added_field {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub level: u32,
+        pub items: Vec<Item>,
+        pub gold: u64,
+    }
+}
+
+mod renamed_field {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub stage: u32,
+        pub items: Vec<Item>,
+    }
+}
+
+mod reordered_fields {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        pub items: Vec<Item>,
+        pub level: u32,
+    }
+}
+
+mod ignored_field {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub struct Item {
+        pub id: u32,
+    }
+
+    #[derive(EasyHash)]
+    pub struct Save {
+        #[easy_hash_ignore]
+        pub level: u32,
+        pub items: Vec<Item>,
+    }
+}
+
+mod renamed_variant {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub enum Event {
+        Begin,
+        Move { x: i32, y: i32 },
+    }
+}
+
+mod tuple_variant {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    pub enum Event {
+        Start,
+        Move(i32, i32),
+    }
+}
+
+#[test]
+fn test_moving_a_type_keeps_its_schema() {
+    assert_eq!(v1::Item::SCHEMA_HASH, moved::Item::SCHEMA_HASH);
+    assert_eq!(v1::Save::SCHEMA_HASH, moved::Save::SCHEMA_HASH);
+}
+
+#[test]
+fn test_field_changes_change_the_schema() {
+    let original = v1::Save::SCHEMA_HASH;
+    for changed in [
+        retyped_nested::Save::SCHEMA_HASH,
+        added_field::Save::SCHEMA_HASH,
+        renamed_field::Save::SCHEMA_HASH,
+        reordered_fields::Save::SCHEMA_HASH,
+        ignored_field::Save::SCHEMA_HASH,
+    ] {
+        assert_ne!(original, changed);
+    }
+}
+
+#[test]
+fn test_variant_changes_change_the_schema() {
+    assert_ne!(v1::Event::SCHEMA_HASH, renamed_variant::Event::SCHEMA_HASH);
+    assert_ne!(v1::Event::SCHEMA_HASH, tuple_variant::Event::SCHEMA_HASH);
+}
+
+#[test]
+fn test_field_options_change_the_schema() {
+    #[derive(EasyHash)]
+    struct Plain {
+        x: f32,
+    }
+
+    mod quantized {
+        use easy_hash::EasyHash;
+
+        #[derive(EasyHash)]
+        pub struct Plain {
+            #[easy_hash(quantize = 0.5)]
+            pub x: f32,
+        }
+    }
+
+    assert_ne!(Plain::SCHEMA_HASH, quantized::Plain::SCHEMA_HASH);
+}
+
+#[test]
+fn test_generic_arguments_are_part_of_the_schema() {
+    #[derive(EasyHash)]
+    struct Wrapper<T> {
+        value: T,
+    }
+
+    assert_ne!(Wrapper::<u32>::SCHEMA_HASH, Wrapper::<u64>::SCHEMA_HASH);
+    assert_ne!(
+        Option::<v1::Item>::SCHEMA_HASH,
+        Option::<retyped_nested::Item>::SCHEMA_HASH
+    );
+    assert_ne!(<(u32, u8)>::SCHEMA_HASH, <(u8, u32)>::SCHEMA_HASH);
+}
+
+#[test]
+fn test_remote_definitions_have_a_schema() {
+    mod foreign {
+        pub struct Vec2 {
+            pub x: f32,
+            pub y: f32,
+        }
+    }
+
+    #[derive(EasyHash)]
+    #[easy_hash(remote = "foreign::Vec2")]
+    struct Vec2Def {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(EasyHash)]
+    struct Body {
+        #[easy_hash(with = "Vec2Def")]
+        position: foreign::Vec2,
+    }
+
+    #[derive(EasyHash)]
+    struct Vec2 {
+        x: f32,
+        y: f32,
+    }
+
+    assert_eq!(Vec2Def::SCHEMA_HASH, Vec2::SCHEMA_HASH);
+    assert_ne!(Body::SCHEMA_HASH, 0);
+}
+
+#[test]
+fn test_recursive_types_have_a_schema() {
+    #[derive(EasyHash)]
+    struct Node {
+        children: Vec<Node>,
+    }
+
+    let tree = Node {
+        children: vec![Node { children: vec![] }],
+    };
+    assert_ne!(tree.ehash(), Node { children: vec![] }.ehash());
+    assert_ne!(Node::SCHEMA_HASH, 0);
+}
+
+#[test]
+fn test_mutually_recursive_types_still_derive() {
+    #[derive(EasyHash)]
+    struct Dir {
+        entries: Vec<Entry>,
+    }
+
+    #[derive(EasyHash)]
+    enum Entry {
+        File(u64),
+        Dir(Dir),
+    }
+
+    let empty = Dir { entries: vec![] };
+    let nested = Dir {
+        entries: vec![Entry::Dir(Dir { entries: vec![] })],
+    };
+    assert_ne!(empty.ehash(), nested.ehash());
+}
+
+#[test]
+fn test_check_rejects_stale_schemas() {
+    let stored = v1::Save::SCHEMA_HASH;
+    assert_eq!(check::<moved::Save>(stored), Ok(()));
+
+    let err = check::<added_field::Save>(stored).unwrap_err();
+    assert_eq!(
+        err,
+        SchemaMismatch {
+            type_name: std::any::type_name::<added_field::Save>(),
+            expected: added_field::Save::SCHEMA_HASH,
+            found: stored,
+        }
+    );
+    assert!(
+        err.to_string()
+            .starts_with("schema of test_schema::added_field::Save changed")
+    );
+}
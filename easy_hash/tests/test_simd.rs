@@ -0,0 +1,113 @@
+use easy_hash::fletcher::{self, Fletcher64};
+use easy_hash::simd::{self, Backend};
+use easy_hash::v2::V2;
+use easy_hash::{EasyHash, HashFormat, split_u64};
+use proptest::prelude::*;
+
+fn backends() -> impl Iterator<Item = Backend> {
+    Backend::ALL
+        .into_iter()
+        .filter(|backend| backend.is_available())
+}
+
+fn assert_backends_agree(words: &[u32]) {
+    let expected = fletcher::calc_fletcher64(words);
+    assert_eq!(
+        simd::calc_fletcher64(words),
+        expected,
+        "len {}",
+        words.len()
+    );
+    for backend in backends() {
+        assert_eq!(
+            simd::calc_fletcher64_with(backend, words),
+            expected,
+            "{backend:?}, len {}",
+            words.len()
+        );
+    }
+}
+
+/// Hash a `Pod` slice the way `bytemuck_slices` did before it was vectorized,
+/// adding the words to a `Fletcher64` one run at a time.
+fn scalar_slice_hash(words_before: &[u32], data: &[u8], address_independent: bool) -> u64 {
+    fn update_unaligned(checksum: &mut Fletcher64, bytes: &[u8]) {
+        for chunk in bytes.chunks(4) {
+            let mut buf = [0u8; 4];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            checksum.update(&[u32::from_le_bytes(buf)]);
+        }
+    }
+
+    let mut checksum = Fletcher64::new();
+    checksum.update(words_before);
+    if address_independent {
+        update_unaligned(&mut checksum, data);
+    } else {
+        let (head, body, tail) = easy_hash::bytemuck::pod_align_to::<u8, u32>(data);
+        update_unaligned(&mut checksum, head);
+        checksum.update(body);
+        update_unaligned(&mut checksum, tail);
+    }
+    checksum.value()
+}
+
+#[test]
+fn test_detected_backend_is_available() {
+    assert!(Backend::detect().is_available());
+    assert!(Backend::Scalar.is_available());
+}
+
+#[test]
+fn test_backends_match_fletcher() {
+    let words: Vec<u32> = (0..200_000u32)
+        .map(|i| i.wrapping_mul(0x9e37_79b9))
+        .collect();
+    for len in (0..300).chain([4095, 4096, 32_767, 32_768, 32_769, 92_679, 92_680, 200_000]) {
+        assert_backends_agree(&words[..len]);
+    }
+}
+
+#[test]
+fn test_backends_match_fletcher_at_extremes() {
+    // The largest words make the lane sums as large as they can get.
+    for word in [0, 1, u32::MAX - 1, u32::MAX] {
+        assert_backends_agree(&vec![word; 100_003]);
+    }
+}
+
+#[test]
+fn test_pod_slice_matches_scalar_path() {
+    let data: Vec<u8> = (0..300_007u32).map(|i| (i * 31 + i / 97) as u8).collect();
+    // Offsets give slices with every alignment, so v1 hashes an unaligned head.
+    for offset in 0..4 {
+        let slice = &data[offset..];
+        let salt = <[u8] as EasyHash>::TYPE_SALT;
+        let len = split_u64(slice.len() as u64);
+
+        let v1_words = [salt];
+        assert_eq!(slice.ehash(), scalar_slice_hash(&v1_words, slice, false));
+
+        let salt64 = split_u64(<[u8] as EasyHash>::TYPE_SALT64);
+        let v2_words = [salt64[0], salt64[1], len[0], len[1]];
+        let v2_checksum = scalar_slice_hash(&v2_words, slice, true);
+        assert_eq!(slice.ehash_in::<V2>(), V2::finish(v2_checksum));
+    }
+}
+
+#[test]
+fn test_pod_array_matches_scalar_path() {
+    let array = [0xabu8; 1001];
+    let salt = <[u8; 1001] as EasyHash>::TYPE_SALT;
+    assert_eq!(array.ehash(), scalar_slice_hash(&[salt], &array, false));
+}
+
+proptest! {
+    #[test]
+    fn prop_backends_match_fletcher(words in prop::collection::vec(any::<u32>(), 0..5_000)) {
+        let expected = fletcher::calc_fletcher64(&words);
+        for backend in backends() {
+            prop_assert_eq!(simd::calc_fletcher64_with(backend, &words), expected);
+        }
+    }
+}
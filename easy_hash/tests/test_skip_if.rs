@@ -0,0 +1,82 @@
+#![cfg(feature = "alloc")]
+
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, u64_to_u32_slice};
+
+#[derive(EasyHash)]
+struct SaveState {
+    hp: u32,
+    #[easy_hash(skip_if_default)]
+    shield: u32,
+    #[easy_hash(skip_if = "Vec::is_empty")]
+    perks: Vec<u8>,
+}
+
+fn manual_hash(field_hashes: &[u64]) -> u64 {
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[SaveState::TYPE_SALT]);
+    checksum.update(u64_to_u32_slice(field_hashes));
+    checksum.value()
+}
+
+#[test]
+fn test_skipped_fields_leave_no_trace() {
+    let state = SaveState {
+        hp: 10,
+        shield: 0,
+        perks: vec![],
+    };
+
+    assert_eq!(state.ehash(), manual_hash(&[10u32.ehash()]));
+}
+
+#[test]
+fn test_non_default_fields_are_hashed() {
+    let state = SaveState {
+        hp: 10,
+        shield: 3,
+        perks: vec![1, 2],
+    };
+
+    assert_eq!(
+        state.ehash(),
+        manual_hash(&[10u32.ehash(), 3u32.ehash(), vec![1u8, 2].ehash()])
+    );
+}
+
+#[test]
+fn test_skip_if_changes_hash_when_predicate_fails() {
+    let a = SaveState {
+        hp: 10,
+        shield: 0,
+        perks: vec![],
+    };
+    let b = SaveState {
+        hp: 10,
+        shield: 0,
+        perks: vec![0],
+    };
+
+    assert_ne!(a.ehash(), b.ehash());
+}
+
+#[derive(EasyHash)]
+#[allow(dead_code)]
+enum Event {
+    Spawn(u32, #[easy_hash(skip_if_default)] u8),
+    Move {
+        dx: i32,
+        #[easy_hash(skip_if_default)]
+        dy: i32,
+    },
+}
+
+#[test]
+fn test_enum_variant_skip_if_default() {
+    let mut checksum = Fletcher64::new();
+    checksum.update(&[Event::TYPE_SALT, 1]);
+    checksum.update(u64_to_u32_slice(&[4i32.ehash()]));
+
+    assert_eq!(Event::Move { dx: 4, dy: 0 }.ehash(), checksum.value());
+    assert_ne!(Event::Spawn(1, 0).ehash(), Event::Spawn(1, 1).ehash());
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "std")]
+
+use std::path::{Path, PathBuf};
+
+use easy_hash::snapshot::{Mode, Outcome, SnapshotError, check, render, snapshot_dir};
+use easy_hash::{EasyHash, assert_ehash_snapshot};
+
+#[derive(EasyHash)]
+struct SaveGame {
+    level: u32,
+    gold: u64,
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("easy_hash_snapshot_{}", std::process::id()));
+    dir.join(format!("{name}.ehash"))
+}
+
+#[test]
+fn test_missing_snapshot_is_created() {
+    let path = snapshot_path("created");
+    let save = SaveGame {
+        level: 3,
+        gold: 120,
+    };
+
+    assert_eq!(
+        check(&path, &save, Mode::Compare).unwrap(),
+        Outcome::Created
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), render(&save));
+    assert_eq!(
+        check(&path, &save, Mode::Compare).unwrap(),
+        Outcome::Matched
+    );
+}
+
+#[test]
+fn test_missing_snapshot_is_required_on_ci() {
+    let path = snapshot_path("required");
+    let save = SaveGame {
+        level: 3,
+        gold: 120,
+    };
+
+    let err = check(&path, &save, Mode::Require).unwrap_err();
+    assert!(matches!(err, SnapshotError::Missing { .. }));
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_mismatch_is_reported() {
+    let path = snapshot_path("mismatch");
+    check(
+        &path,
+        &SaveGame {
+            level: 3,
+            gold: 120,
+        },
+        Mode::Compare,
+    )
+    .unwrap();
+
+    let changed = SaveGame {
+        level: 3,
+        gold: 121,
+    };
+    let err = check(&path, &changed, Mode::Compare).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("does not match"), "{message}");
+    assert!(
+        message.contains(&format!("{:#018x}", changed.ehash())),
+        "{message}"
+    );
+    assert!(
+        message.contains("EASY_HASH_UPDATE_SNAPSHOTS=1"),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_update_overwrites_mismatch() {
+    let path = snapshot_path("update");
+    check(
+        &path,
+        &SaveGame {
+            level: 3,
+            gold: 120,
+        },
+        Mode::Compare,
+    )
+    .unwrap();
+
+    let changed = SaveGame {
+        level: 4,
+        gold: 120,
+    };
+    assert_eq!(
+        check(&path, &changed, Mode::Update).unwrap(),
+        Outcome::Updated
+    );
+    assert_eq!(
+        check(&path, &changed, Mode::Require).unwrap(),
+        Outcome::Matched
+    );
+}
+
+#[test]
+fn test_only_the_hash_is_compared() {
+    let path = snapshot_path("dump");
+    let save = SaveGame {
+        level: 3,
+        gold: 120,
+    };
+    let edited = render(&save).replace("type: ", "type (renamed): ");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, edited).unwrap();
+
+    assert_eq!(
+        check(&path, &save, Mode::Require).unwrap(),
+        Outcome::Matched
+    );
+}
+
+#[test]
+fn test_snapshot_dir_is_next_to_the_test() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let expected = Path::new(manifest_dir).join("tests").join("snapshots");
+    assert_eq!(snapshot_dir(manifest_dir, file!()), expected);
+}
+
+#[test]
+fn test_macro_uses_snapshot_next_to_the_test() {
+    let save = SaveGame { level: 7, gold: 9 };
+    let dir = snapshot_dir(env!("CARGO_MANIFEST_DIR"), file!());
+    let path = dir.join("test_macro_fixture.ehash");
+    check(&path, &save, Mode::Update).unwrap();
+
+    let changed = SaveGame { level: 7, gold: 10 };
+    let mismatch = std::panic::catch_unwind(|| {
+        assert_ehash_snapshot!("test_macro_fixture", changed);
+    });
+    let matched = std::panic::catch_unwind(|| {
+        assert_ehash_snapshot!("test_macro_fixture", save);
+    });
+
+    std::fs::remove_file(&path).unwrap();
+    let _ = std::fs::remove_dir(&dir);
+    // The mismatch is only tolerated when snapshots are being updated.
+    if Mode::from_env() != Mode::Update {
+        assert!(mismatch.is_err());
+    }
+    assert!(matched.is_ok());
+}
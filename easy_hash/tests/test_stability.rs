@@ -0,0 +1,30 @@
+#![cfg(feature = "alloc")]
+
+use easy_hash::stability::vectors;
+
+#[test]
+fn test_reference_hashes() {
+    let mismatches: Vec<String> = vectors()
+        .iter()
+        .filter(|vector| !vector.matches())
+        .map(|vector| vector.to_string())
+        .collect();
+    assert!(
+        mismatches.is_empty(),
+        "hashes changed:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_vector_names_are_unique() {
+    let vectors = vectors();
+    for (i, vector) in vectors.iter().enumerate() {
+        assert!(!vector.name.contains('\n'), "{:?}", vector.name);
+        assert!(
+            vectors[..i].iter().all(|other| other.name != vector.name),
+            "duplicate vector {:?}",
+            vector.name
+        );
+    }
+}
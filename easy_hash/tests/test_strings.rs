@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use easy_hash::EasyHash;
 use test_case::test_case;
 
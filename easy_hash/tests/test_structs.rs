@@ -1,4 +1,5 @@
-use easy_hash::EasyHash;
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, u64_to_u32_slice};
 use test_case::test_case;
 
 #[derive(EasyHash)]
@@ -92,6 +93,7 @@ fn test_tup_of_struct_ne_when_reordered(a: f32, b: f32, c: u8, d: u8) {
     assert_ne!((&bb, &aa).ehash(), (&aa, &bb).ehash());
 }
 
+#[cfg(feature = "alloc")]
 #[test_case(0.0, -0.1, 0, 1 ; "first case")]
 fn test_vec_of_struct_ne_when_reordered(a: f32, b: f32, c: u8, d: u8) {
     let aa = TestStruct { a, b, c, d };
@@ -140,3 +142,28 @@ fn test_struct_with_ignored_field() {
     // should be equal if only ignored fields are different
     assert_eq!(a.ehash(), b.ehash());
 }
+
+#[derive(EasyHash)]
+#[allow(dead_code)]
+struct TestTupStructIgnore(u8, #[easy_hash_ignore] f32);
+
+#[test]
+fn test_tup_struct_with_ignored_field() {
+    let a = TestTupStructIgnore(1, 2.0);
+    let b = TestTupStructIgnore(1, 3.0);
+    let c = TestTupStructIgnore(2, 2.0);
+    assert_eq!(a.ehash(), b.ehash());
+    assert_ne!(a.ehash(), c.ehash());
+}
+
+#[test]
+fn test_tup_struct_ignored_field_changed_hash() {
+    // Before ignored fields of tuple structs were skipped, they were hashed like
+    // the others.
+    let value = TestTupStructIgnore(1, 2.0);
+    let mut before = Fletcher64::new();
+    before.update(&[TestTupStructIgnore::TYPE_SALT]);
+    before.update(u64_to_u32_slice(&[1u8.ehash(), 2.0f32.ehash()]));
+    assert_eq!(before.value(), 0xccf8_9d58_4a54_a2ee);
+    assert_eq!(value.ehash(), 0x80be_1505_3dae_86a0);
+}
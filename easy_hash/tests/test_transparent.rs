@@ -0,0 +1,72 @@
+use easy_hash::EasyHash;
+use std::marker::PhantomData;
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Meters(f32);
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct PlayerId {
+    id: u32,
+}
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+#[allow(dead_code)]
+struct Tagged<U> {
+    value: u64,
+    #[easy_hash_ignore]
+    unit: PhantomData<U>,
+}
+
+#[derive(EasyHash)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Wrapped(Position);
+
+#[test]
+fn test_transparent_tuple_struct_matches_inner() {
+    assert_eq!(Meters(1.5).ehash(), 1.5f32.ehash());
+    assert_eq!(Meters::TYPE_SALT, f32::TYPE_SALT);
+}
+
+#[test]
+fn test_transparent_named_struct_matches_inner() {
+    assert_eq!(PlayerId { id: 7 }.ehash(), 7u32.ehash());
+    assert_eq!(PlayerId::TYPE_SALT, u32::TYPE_SALT);
+}
+
+#[test]
+fn test_transparent_skips_ignored_fields() {
+    let tagged = Tagged::<u8> {
+        value: 99,
+        unit: PhantomData,
+    };
+    assert_eq!(tagged.ehash(), 99u64.ehash());
+    assert_eq!(Tagged::<u8>::TYPE_SALT, u64::TYPE_SALT);
+}
+
+#[test]
+fn test_transparent_forwards_to_derived_type() {
+    let position = Position { x: 1.0, y: -2.0 };
+    let wrapped = Wrapped(Position { x: 1.0, y: -2.0 });
+    assert_eq!(wrapped.ehash(), position.ehash());
+    assert_eq!(Wrapped::TYPE_SALT, Position::TYPE_SALT);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_transparent_inside_containers() {
+    // Containers salt themselves with the name of their element type, so only
+    // the top-level hash of a newtype matches its inner value.
+    let meters = vec![Meters(1.0), Meters(2.0)];
+    let floats = vec![1.0f32, 2.0f32];
+    assert_ne!(meters.ehash(), floats.ehash());
+    assert_ne!(Some(Meters(3.0)).ehash(), Some(3.0f32).ehash());
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "tree")]
+
+use std::collections::BTreeMap;
+
+use easy_hash::EasyHash;
+
+#[derive(EasyHash, Clone)]
+struct Stats {
+    hp: u32,
+    mana: u32,
+}
+
+#[derive(EasyHash, Clone)]
+#[allow(dead_code)]
+enum State {
+    Idle,
+    Walking { speed: i32 },
+    Casting(u8, #[easy_hash_ignore] u8),
+}
+
+#[derive(EasyHash, Clone)]
+struct Unit {
+    name: String,
+    stats: Stats,
+    state: State,
+    inventory: Vec<(u8, Option<u16>)>,
+    #[easy_hash(skip_if_default)]
+    level: u32,
+}
+
+fn unit() -> Unit {
+    Unit {
+        name: "goblin".to_string(),
+        stats: Stats { hp: 10, mana: 3 },
+        state: State::Walking { speed: 2 },
+        inventory: vec![(1, Some(4)), (2, None)],
+        level: 0,
+    }
+}
+
+#[test]
+fn test_every_node_matches_ehash() {
+    let unit = unit();
+    let tree = unit.ehash_tree();
+
+    assert_eq!(tree.hash, unit.ehash());
+    assert_eq!(tree.type_salt, Unit::TYPE_SALT);
+    assert_eq!(tree.get("stats").unwrap().hash, unit.stats.ehash());
+    assert_eq!(tree.get("stats.hp").unwrap().hash, 10u32.ehash());
+    assert_eq!(tree.get("state").unwrap().hash, unit.state.ehash());
+    assert_eq!(tree.get("inventory").unwrap().hash, unit.inventory.ehash());
+    assert_eq!(tree.get("inventory[0].1.Some").unwrap().hash, 4u16.ehash());
+}
+
+#[test]
+fn test_tree_paths() {
+    let paths: Vec<String> = unit().ehash_tree().iter().map(|(path, _)| path).collect();
+
+    assert_eq!(
+        paths,
+        [
+            "",
+            "name",
+            "stats",
+            "stats.hp",
+            "stats.mana",
+            "state",
+            "state.Walking",
+            "state.Walking.speed",
+            "inventory",
+            "inventory[0]",
+            "inventory[0].0",
+            "inventory[0].1",
+            "inventory[0].1.Some",
+            "inventory[1]",
+            "inventory[1].0",
+            "inventory[1].1",
+        ]
+    );
+}
+
+#[test]
+fn test_skipped_and_ignored_fields_have_no_node() {
+    let mut unit = unit();
+    unit.state = State::Casting(1, 2);
+    let tree = unit.ehash_tree();
+
+    assert_eq!(tree.hash, unit.ehash());
+    assert!(tree.get("level").is_none());
+    assert!(tree.get("state.Casting.0").is_some());
+    assert!(tree.get("state.Casting.1").is_none());
+
+    unit.level = 3;
+    assert_eq!(unit.ehash_tree().get("level").unwrap().hash, 3u32.ehash());
+}
+
+#[test]
+fn test_changed_field_changes_its_path_only() {
+    let a = unit();
+    let mut b = unit();
+    b.stats.mana = 4;
+    let (a, b) = (a.ehash_tree(), b.ehash_tree());
+
+    assert_ne!(a.hash, b.hash);
+    assert_ne!(a.get("stats.mana"), b.get("stats.mana"));
+    assert_eq!(a.get("stats.hp"), b.get("stats.hp"));
+    assert_eq!(a.get("inventory"), b.get("inventory"));
+}
+
+#[test]
+fn test_unit_variant_and_display() {
+    let mut unit = unit();
+    unit.state = State::Idle;
+    let tree = unit.ehash_tree();
+
+    assert!(tree.get("state.Idle").is_some());
+    let text = tree.to_string();
+    assert!(text.starts_with("test_tree::Unit = 0x"), "{text}");
+    assert!(text.contains("\n  stats: test_tree::Stats = 0x"), "{text}");
+}
+
+#[test]
+fn test_btree_map_has_a_node_per_entry() {
+    let a: BTreeMap<String, Stats> = [
+        ("archer".to_string(), Stats { hp: 8, mana: 0 }),
+        ("mage".to_string(), Stats { hp: 5, mana: 9 }),
+    ]
+    .into();
+    let mut b = a.clone();
+    b.get_mut("mage").unwrap().mana = 8;
+    let tree = a.ehash_tree();
+
+    assert_eq!(tree.hash, a.ehash());
+    assert_eq!(
+        tree.get("[1].key").unwrap().hash,
+        "mage".to_string().ehash()
+    );
+    assert_eq!(
+        tree.get("[0]").unwrap().hash,
+        ("archer".to_string(), Stats { hp: 8, mana: 0 }).ehash()
+    );
+    assert_eq!(
+        easy_hash::diff(&a, &b).paths().collect::<Vec<_>>(),
+        ["[1].value.mana"]
+    );
+}
@@ -1,4 +1,5 @@
-use easy_hash::type_salt_generic;
+use const_fnv1a_hash::{fnv1a_hash_32, fnv1a_hash_str_64};
+use easy_hash::{type_salt_generic, type_salt_of, type_salt64_of};
 
 #[test]
 fn test_type_salt_generic_deterministic() {
@@ -17,3 +18,47 @@ fn test_type_salt_generic_differs_for_generic() {
     assert_ne!(baseline, different_generic);
     assert_ne!(baseline, swapped);
 }
+
+#[test]
+fn test_type_salt_generic_is_unchanged() {
+    // The original implementation, which concatenated the bytes by hand.
+    let main = fnv1a_hash_str_64(std::any::type_name::<Option<u8>>()).to_ne_bytes();
+    let generic = fnv1a_hash_str_64(std::any::type_name::<u16>()).to_ne_bytes();
+    let combined: Vec<u8> = main.iter().chain(&generic).copied().collect();
+
+    assert_eq!(
+        type_salt_generic::<Option<u8>, u16>(),
+        fnv1a_hash_32(&combined, None)
+    );
+}
+
+#[test]
+fn test_type_salt_of_matches_type_salt_generic() {
+    assert_eq!(
+        type_salt_of!(Option<u8>, u16),
+        type_salt_generic::<Option<u8>, u16>()
+    );
+}
+
+#[test]
+fn test_variadic_salts_depend_on_every_type_and_their_order() {
+    const SALTS: [u32; 4] = [
+        type_salt_of!(u8),
+        type_salt_of!(u8, u16),
+        type_salt_of!(u8, u16, u32),
+        type_salt_of!(u8, u32, u16),
+    ];
+    for (i, salt) in SALTS.iter().enumerate() {
+        assert!(!SALTS[..i].contains(salt), "{SALTS:?}");
+    }
+
+    const SALTS64: [u64; 4] = [
+        type_salt64_of!(u8),
+        type_salt64_of!(u8, u16),
+        type_salt64_of!(u8, u16, u32),
+        type_salt64_of!(u8, u32, u16),
+    ];
+    for (i, salt) in SALTS64.iter().enumerate() {
+        assert!(!SALTS64[..i].contains(salt), "{SALTS64:?}");
+    }
+}
@@ -0,0 +1,117 @@
+use easy_hash::{EasyHash, EasyHashVersioned};
+
+#[derive(EasyHash)]
+struct Unversioned {
+    gold: u32,
+}
+
+mod v1 {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    #[easy_hash(version = 1)]
+    pub struct Save {
+        pub gold: u32,
+    }
+}
+
+mod v2 {
+    use easy_hash::EasyHash;
+
+    #[derive(EasyHash)]
+    #[easy_hash(version = 2)]
+    pub struct Save {
+        pub gold: u32,
+    }
+}
+
+mod v3 {
+    use easy_hash::EasyHash;
+
+    /// Gold became a `u64` in v2, and gems were added in v3.
+    #[derive(EasyHash)]
+    #[easy_hash(version = 3, previous(v1 = "Save::hash_v1", v2 = "Save::hash_v2"))]
+    pub struct Save {
+        pub gold: u64,
+        pub gems: u32,
+    }
+
+    impl Save {
+        fn hash_v1(&self) -> u64 {
+            super::v1::Save {
+                gold: self.gold as u32,
+            }
+            .ehash()
+        }
+
+        fn hash_v2(&self) -> u64 {
+            super::v2::Save {
+                gold: self.gold as u32,
+            }
+            .ehash()
+        }
+    }
+}
+
+#[derive(EasyHash)]
+#[easy_hash(version = 4)]
+enum Event {
+    Start,
+    Move { x: i32, y: i32 },
+}
+
+#[derive(EasyHash)]
+enum UnversionedEvent {
+    Start,
+    Move { x: i32, y: i32 },
+}
+
+#[test]
+fn test_version_is_part_of_the_hash() {
+    let hashes = [
+        Unversioned { gold: 7 }.ehash(),
+        v1::Save { gold: 7 }.ehash(),
+        v2::Save { gold: 7 }.ehash(),
+    ];
+    assert_ne!(hashes[0], hashes[1]);
+    assert_ne!(hashes[1], hashes[2]);
+    assert_ne!(hashes[0], hashes[2]);
+
+    assert_ne!(Event::Start.ehash(), UnversionedEvent::Start.ehash());
+    assert_ne!(
+        Event::Move { x: 1, y: 2 }.ehash(),
+        UnversionedEvent::Move { x: 1, y: 2 }.ehash()
+    );
+}
+
+#[test]
+fn test_version_constant() {
+    assert_eq!(v1::Save::VERSION, 1);
+    assert_eq!(v3::Save::VERSION, 3);
+    assert_eq!(Event::VERSION, 4);
+}
+
+#[test]
+fn test_ehash_as_version_reproduces_legacy_hashes() {
+    let stored_v1 = v1::Save { gold: 7 }.ehash();
+    let stored_v2 = v2::Save { gold: 7 }.ehash();
+    let migrated = v3::Save { gold: 7, gems: 0 };
+
+    assert_eq!(migrated.ehash_as_version(1), Some(stored_v1));
+    assert_eq!(migrated.ehash_as_version(2), Some(stored_v2));
+    assert_eq!(migrated.ehash_as_version(3), Some(migrated.ehash()));
+}
+
+#[test]
+fn test_unregistered_versions_have_no_hash() {
+    let save = v3::Save { gold: 7, gems: 0 };
+    assert_eq!(save.ehash_as_version(0), None);
+    assert_eq!(save.ehash_as_version(4), None);
+    assert_eq!(Event::Start.ehash_as_version(3), None);
+    assert_eq!(Event::Start.ehash_as_version(4), Some(Event::Start.ehash()));
+}
+
+#[test]
+fn test_version_is_part_of_the_schema() {
+    assert_ne!(v1::Save::SCHEMA_HASH, v2::Save::SCHEMA_HASH);
+}
@@ -1,4 +1,5 @@
-use easy_hash::EasyHash;
+use easy_hash::fletcher::Fletcher64;
+use easy_hash::{EasyHash, u64_to_u32_slice};
 
 #[derive(EasyHash)]
 enum TestEnum1 {
@@ -228,3 +229,41 @@ fn test_enum_with_multiple_fields() {
     assert_ne!(b1.ehash(), b3.ehash());
     assert_ne!(a1.ehash(), b1.ehash());
 }
+
+#[derive(EasyHash)]
+#[allow(dead_code)]
+enum EnumWithIgnoredFields {
+    A(u8, #[easy_hash_ignore] u16),
+    B {
+        x: u8,
+        #[easy_hash_ignore]
+        y: u16,
+    },
+}
+
+#[test]
+fn test_enum_ignored_fields() {
+    let a1 = EnumWithIgnoredFields::A(1, 2);
+    let a2 = EnumWithIgnoredFields::A(1, 20);
+    let a3 = EnumWithIgnoredFields::A(10, 2);
+    assert_eq!(a1.ehash(), a2.ehash());
+    assert_ne!(a1.ehash(), a3.ehash());
+
+    let b1 = EnumWithIgnoredFields::B { x: 1, y: 2 };
+    let b2 = EnumWithIgnoredFields::B { x: 1, y: 20 };
+    let b3 = EnumWithIgnoredFields::B { x: 10, y: 2 };
+    assert_eq!(b1.ehash(), b2.ehash());
+    assert_ne!(b1.ehash(), b3.ehash());
+}
+
+#[test]
+fn test_enum_ignored_tuple_field_changed_hash() {
+    // Before ignored fields of tuple variants were skipped, they were hashed like
+    // the others.
+    let value = EnumWithIgnoredFields::A(1, 2);
+    let mut before = Fletcher64::new();
+    before.update(&[EnumWithIgnoredFields::TYPE_SALT, 0]);
+    before.update(u64_to_u32_slice(&[1u8.ehash(), 2u16.ehash()]));
+    assert_eq!(before.value(), 0xd88c_dac2_24f1_5263);
+    assert_eq!(value.ehash(), 0x8286_6c3d_b727_0100);
+}
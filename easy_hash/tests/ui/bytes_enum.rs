@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(bytes)]
+enum Flag {
+    On,
+    Off,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+struct Body {
+    #[easy_hash(quantize = 1e-4, ulps = 4)]
+    x: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+struct SaveState {
+    #[easy_hash(skip_if_default, skip_if = "Vec::is_empty")]
+    perks: Vec<u8>,
+}
+
+fn main() {}
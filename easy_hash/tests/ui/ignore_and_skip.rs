@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+struct SaveState {
+    #[easy_hash_ignore]
+    #[easy_hash(skip_if_default)]
+    shield: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+struct Cached {
+    value: u32,
+    #[easy_hash_ignore(true)]
+    cache: u64,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(version = 2, previous(v3 = "Save::hash_v3"))]
+struct Save {
+    gold: u64,
+}
+
+impl Save {
+    fn hash_v3(&self) -> u64 {
+        self.gold
+    }
+}
+
+fn main() {}
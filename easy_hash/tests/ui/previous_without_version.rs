@@ -0,0 +1,15 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(previous(v1 = "Save::hash_v1"))]
+struct Save {
+    gold: u64,
+}
+
+impl Save {
+    fn hash_v1(&self) -> u64 {
+        self.gold
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+enum Either {
+    Left(u32),
+    Right(u32),
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Meters(#[easy_hash(group = "physics")] f32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+fn main() {}
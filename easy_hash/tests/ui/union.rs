@@ -0,0 +1,9 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
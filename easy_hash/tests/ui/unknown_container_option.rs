@@ -0,0 +1,7 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+#[easy_hash(transparant)]
+struct Meters(f32);
+
+fn main() {}
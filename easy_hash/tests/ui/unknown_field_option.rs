@@ -0,0 +1,9 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+struct SaveState {
+    #[easy_hash(skip_if_defualt)]
+    shield: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use easy_hash::EasyHash;
+
+#[derive(EasyHash)]
+enum Event {
+    Spawn,
+    #[easy_hash_ignore]
+    Despawn,
+}
+
+fn main() {}
@@ -0,0 +1,464 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Attribute, DeriveInput, ExprPath, Field, Lit, LitInt, LitStr, Type, Variant};
+
+const CONTAINER_OPTIONS: &str = "`bytes`, `previous`, `remote`, `transparent`, `version`";
+const FIELD_OPTIONS: &str = "`group`, `quantize`, `skip_if`, `skip_if_default`, `ulps`, `with`";
+
+/// Options parsed from the `#[easy_hash(...)]` attributes on the deriving type.
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    /// Set by `transparent`; the span points at the option for error reporting.
+    pub transparent: Option<Span>,
+    /// Set by `bytes`; the span points at the option for error reporting.
+    pub bytes: Option<Span>,
+    /// The foreign type named by `remote = "..."`.
+    pub remote: Option<Type>,
+    /// The layout version declared by `version = N`, which is folded into the hash.
+    pub version: Option<Version>,
+    /// The hash functions of older layouts, registered with `previous(vN = "path")`.
+    pub previous: Vec<Previous>,
+}
+
+/// A `version = N` container option.
+pub(crate) struct Version {
+    pub value: u32,
+    pub span: Span,
+}
+
+/// A `vN = "path"` entry of the `previous(...)` container option.
+pub(crate) struct Previous {
+    pub version: Version,
+    /// A `fn(&Self) -> u64` returning the hash the value had as that version.
+    pub hash: ExprPath,
+}
+
+impl ContainerAttrs {
+    pub(crate) fn from_input(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = ContainerAttrs::default();
+
+        for attr in &input.attrs {
+            if attr.path().is_ident("easy_hash_ignore") {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "#[easy_hash_ignore] can only be used on fields",
+                ));
+            }
+            if !attr.path().is_ident("easy_hash") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("remote") {
+                    if attrs.remote.is_some() {
+                        return Err(meta.error("duplicate easy_hash option"));
+                    }
+                    let lit: LitStr = meta.value()?.parse()?;
+                    attrs.remote = Some(lit.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("version") {
+                    if attrs.version.is_some() {
+                        return Err(meta.error("duplicate easy_hash option"));
+                    }
+                    attrs.version = Some(parse_version(&meta)?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("previous") {
+                    return meta.parse_nested_meta(|inner| {
+                        let previous = parse_previous(&inner)?;
+                        if attrs
+                            .previous
+                            .iter()
+                            .any(|p| p.version.value == previous.version.value)
+                        {
+                            return Err(inner.error(format!(
+                                "duplicate easy_hash previous version {}",
+                                previous.version.value
+                            )));
+                        }
+                        attrs.previous.push(previous);
+                        Ok(())
+                    });
+                }
+
+                let slot = if meta.path.is_ident("transparent") {
+                    &mut attrs.transparent
+                } else if meta.path.is_ident("bytes") {
+                    &mut attrs.bytes
+                } else {
+                    return Err(unknown_option(&meta, "container", CONTAINER_OPTIONS));
+                };
+
+                if slot.is_some() {
+                    return Err(meta.error("duplicate easy_hash option"));
+                }
+                *slot = Some(meta.path.span());
+                Ok(())
+            })?;
+        }
+
+        if let (Some(_), Some(span)) = (attrs.transparent, attrs.bytes) {
+            return Err(syn::Error::new(
+                span,
+                "conflicting easy_hash options: use only one of `transparent` and `bytes`",
+            ));
+        }
+        if let (Some(remote), Some(span)) = (&attrs.remote, attrs.transparent.or(attrs.bytes)) {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "conflicting easy_hash options: `remote = \"{}\"` cannot be combined with `transparent` or `bytes`",
+                    quote!(#remote).to_string().replace(' ', "")
+                ),
+            ));
+        }
+
+        if let (Some(_), Some(span)) = (
+            &attrs.version,
+            attrs
+                .transparent
+                .or(attrs.bytes)
+                .or(attrs.remote.as_ref().map(|remote| remote.span())),
+        ) {
+            return Err(syn::Error::new(
+                span,
+                "conflicting easy_hash options: `version` cannot be combined with `transparent`, `bytes` or `remote`",
+            ));
+        }
+        match (&attrs.version, attrs.previous.first()) {
+            (None, Some(previous)) => {
+                return Err(syn::Error::new(
+                    previous.version.span,
+                    "`previous` requires the type to declare its own `version`",
+                ));
+            }
+            (Some(version), _) => {
+                if let Some(previous) = attrs
+                    .previous
+                    .iter()
+                    .find(|p| p.version.value >= version.value)
+                {
+                    return Err(syn::Error::new(
+                        previous.version.span,
+                        format!(
+                            "previous versions must be older than the current version {}",
+                            version.value
+                        ),
+                    ));
+                }
+            }
+            (None, None) => {}
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// Options parsed from the `#[easy_hash_ignore]` and `#[easy_hash(...)]` attributes
+/// on a single field.
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// The field is marked `#[easy_hash_ignore]`.
+    pub ignore: bool,
+    /// Condition under which the field is left out of the hash.
+    pub skip_if: Option<SkipIf>,
+    /// Path to a remote definition whose `ehash` function hashes the field.
+    pub with: Option<ExprPath>,
+    /// How the field's floats are snapped before hashing.
+    pub quantize: Option<Quantize>,
+    /// The groups named by `group = "..."`, whose `ehash_group` hashes include the field.
+    pub groups: Vec<LitStr>,
+}
+
+/// A `quantize = step` or `ulps = n` field option.
+pub(crate) enum Quantize {
+    Step(f64),
+    Ulps(u32),
+}
+
+/// A `skip_if_default` or `skip_if = "path"` field option.
+pub(crate) enum SkipIf {
+    Default,
+    Predicate(ExprPath),
+}
+
+impl FieldAttrs {
+    pub(crate) fn from_field(field: &Field) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+        let mut skip_if_span = None;
+        let mut quantize_span = None;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("easy_hash_ignore") {
+                if let Err(err) = attr.meta.require_path_only() {
+                    return Err(syn::Error::new(
+                        err.span(),
+                        "#[easy_hash_ignore] does not take arguments",
+                    ));
+                }
+                if attrs.ignore {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "duplicate #[easy_hash_ignore] attribute",
+                    ));
+                }
+                attrs.ignore = true;
+                continue;
+            }
+            if !attr.path().is_ident("easy_hash") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("with") {
+                    if attrs.with.is_some() {
+                        return Err(meta.error("duplicate easy_hash option `with`"));
+                    }
+                    let lit: LitStr = meta.value()?.parse()?;
+                    attrs.with = Some(lit.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("group") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    if attrs.groups.iter().any(|g| g.value() == lit.value()) {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!("duplicate easy_hash group `{}`", lit.value()),
+                        ));
+                    }
+                    attrs.groups.push(lit);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("quantize") || meta.path.is_ident("ulps") {
+                    if attrs.quantize.is_some() {
+                        return Err(meta.error(
+                            "conflicting easy_hash options: use only one of `quantize` and `ulps`",
+                        ));
+                    }
+                    attrs.quantize = Some(if meta.path.is_ident("quantize") {
+                        Quantize::Step(parse_step(&meta)?)
+                    } else {
+                        let lit: LitInt = meta.value()?.parse()?;
+                        match lit.base10_parse::<u32>()? {
+                            0 => return Err(syn::Error::new(lit.span(), "`ulps` must be at least 1")),
+                            ulps => Quantize::Ulps(ulps),
+                        }
+                    });
+                    quantize_span = Some(meta.path.span());
+                    return Ok(());
+                }
+
+                let skip_if = if meta.path.is_ident("skip_if_default") {
+                    SkipIf::Default
+                } else if meta.path.is_ident("skip_if") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    SkipIf::Predicate(lit.parse()?)
+                } else {
+                    return Err(unknown_option(&meta, "field", FIELD_OPTIONS));
+                };
+
+                if attrs.skip_if.is_some() {
+                    return Err(meta.error(
+                        "conflicting easy_hash options: use only one of `skip_if` and `skip_if_default`",
+                    ));
+                }
+                attrs.skip_if = Some(skip_if);
+                skip_if_span = Some(meta.path.span());
+                Ok(())
+            })?;
+        }
+
+        let with_span = attrs.with.as_ref().map(|with| with.span());
+        let group_span = attrs.groups.first().map(|group| group.span());
+        if let (true, Some(span)) = (
+            attrs.ignore,
+            skip_if_span.or(with_span).or(quantize_span).or(group_span),
+        ) {
+            return Err(syn::Error::new(
+                span,
+                "conflicting easy_hash options: the field is already marked #[easy_hash_ignore]",
+            ));
+        }
+        if let (Some(_), Some(span)) = (with_span, quantize_span) {
+            return Err(syn::Error::new(
+                span,
+                "conflicting easy_hash options: `with` cannot be combined with `quantize` or `ulps`",
+            ));
+        }
+
+        Ok(attrs)
+    }
+}
+
+impl ContainerAttrs {
+    /// Describe the options that change how the type is hashed, for its schema.
+    pub(crate) fn describe(&self) -> String {
+        let options: Vec<String> = [
+            self.transparent.map(|_| "transparent".to_string()),
+            self.bytes.map(|_| "bytes".to_string()),
+            self.version
+                .as_ref()
+                .map(|version| format!("version = {}", version.value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        describe_options(&options)
+    }
+}
+
+impl FieldAttrs {
+    /// Describe the options that change how the field is hashed, for its schema.
+    pub(crate) fn describe(&self) -> String {
+        if self.ignore {
+            return " #[easy_hash_ignore]".to_string();
+        }
+        let mut options = Vec::new();
+        match &self.skip_if {
+            Some(SkipIf::Default) => options.push("skip_if_default".to_string()),
+            Some(SkipIf::Predicate(path)) => options.push(format!(
+                "skip_if = \"{}\"",
+                quote!(#path).to_string().replace(' ', "")
+            )),
+            None => {}
+        }
+        if self.with.is_some() {
+            options.push("with".to_string());
+        }
+        match self.quantize {
+            Some(Quantize::Step(step)) => options.push(format!("quantize = {step:?}")),
+            Some(Quantize::Ulps(ulps)) => options.push(format!("ulps = {ulps}")),
+            None => {}
+        }
+        for group in &self.groups {
+            options.push(format!("group = {:?}", group.value()));
+        }
+        describe_options(&options)
+    }
+
+    /// Generate a boolean expression that is true when `group` names one of the
+    /// field's groups.
+    pub(crate) fn in_group(&self, group: &TokenStream) -> TokenStream {
+        let groups = &self.groups;
+        quote! { matches!(#group, #(#groups)|*) }
+    }
+}
+
+impl SkipIf {
+    /// Generate a boolean expression that is true when the field should be skipped.
+    /// `access` must evaluate to a reference to the field.
+    pub(crate) fn condition(&self, field: &Field, access: &TokenStream) -> TokenStream {
+        match self {
+            SkipIf::Default => {
+                let ty = &field.ty;
+                quote! { *#access == <#ty as ::core::default::Default>::default() }
+            }
+            SkipIf::Predicate(path) => quote! { #path(#access) },
+        }
+    }
+}
+
+impl Quantize {
+    /// Generate the `easy_hash::Quantization` value for this option.
+    pub(crate) fn to_tokens(&self) -> TokenStream {
+        match self {
+            Quantize::Step(step) => quote! { easy_hash::Quantization::Step(#step) },
+            Quantize::Ulps(ulps) => quote! { easy_hash::Quantization::Ulps(#ulps) },
+        }
+    }
+}
+
+/// Write options the way they appear in an `#[easy_hash(...)]` attribute, or nothing
+/// if there are none.
+fn describe_options(options: &[impl AsRef<str>]) -> String {
+    if options.is_empty() {
+        return String::new();
+    }
+    let options: Vec<&str> = options.iter().map(AsRef::as_ref).collect();
+    format!(" #[easy_hash({})]", options.join(", "))
+}
+
+/// Parse the value of `version = N`, which must be at least 1.
+fn parse_version(meta: &ParseNestedMeta) -> syn::Result<Version> {
+    let lit: LitInt = meta.value()?.parse()?;
+    match lit.base10_parse::<u32>()? {
+        0 => Err(syn::Error::new(lit.span(), "`version` must be at least 1")),
+        value => Ok(Version {
+            value,
+            span: lit.span(),
+        }),
+    }
+}
+
+/// Parse a `vN = "path"` entry of `previous(...)`.
+fn parse_previous(meta: &ParseNestedMeta) -> syn::Result<Previous> {
+    let version = meta
+        .path
+        .get_ident()
+        .and_then(|ident| ident.to_string().strip_prefix('v')?.parse::<u32>().ok())
+        .filter(|&version| version > 0)
+        .ok_or_else(|| meta.error("expected a previous version such as `v1 = \"path\"`"))?;
+    let lit: LitStr = meta.value()?.parse()?;
+    Ok(Previous {
+        version: Version {
+            value: version,
+            span: meta.path.span(),
+        },
+        hash: lit.parse()?,
+    })
+}
+
+/// Parse the grid step of `quantize = ...`, which may be written as a float or an integer.
+fn parse_step(meta: &ParseNestedMeta) -> syn::Result<f64> {
+    let lit: Lit = meta.value()?.parse()?;
+    let step = match &lit {
+        Lit::Float(lit) => lit.base10_parse::<f64>()?,
+        Lit::Int(lit) => lit.base10_parse::<f64>()?,
+        _ => return Err(syn::Error::new(lit.span(), "expected a number")),
+    };
+    if !(step.is_finite() && step > 0.0) {
+        return Err(syn::Error::new(
+            lit.span(),
+            "`quantize` must be a positive, finite step",
+        ));
+    }
+    Ok(step)
+}
+
+/// Enum variants take no easy_hash options; reject them rather than silently ignoring them.
+pub(crate) fn reject_variant_attrs(variant: &Variant) -> syn::Result<()> {
+    match variant.attrs.iter().find(|a| is_easy_hash_attr(a)) {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            "easy_hash attributes are not supported on enum variants",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn is_easy_hash_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("easy_hash") || attr.path().is_ident("easy_hash_ignore")
+}
+
+/// Build the error for an unrecognized option inside `#[easy_hash(...)]`.
+fn unknown_option(meta: &ParseNestedMeta, kind: &str, supported: &str) -> syn::Error {
+    let path = &meta.path;
+    let name = quote!(#path).to_string().replace(' ', "");
+
+    if matches!(name.as_str(), "ignore" | "skip") {
+        meta.error(format!(
+            "unknown easy_hash {kind} option `{name}`; use #[easy_hash_ignore] to leave a field out of the hash"
+        ))
+    } else {
+        meta.error(format!(
+            "unknown easy_hash {kind} option `{name}`; supported options: {supported}"
+        ))
+    }
+}
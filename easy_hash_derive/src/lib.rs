@@ -1,39 +1,201 @@
-use proc_macro2::{Ident, TokenStream};
+mod attr;
+mod schema;
+
+use attr::{ContainerAttrs, FieldAttrs};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Fields, FieldsNamed, FieldsUnnamed, GenericParam,
-    Generics, Index, Variant, parse_macro_input, parse_quote,
+    Data, DataEnum, DataStruct, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed,
+    GenericParam, Generics, Index, LitStr, PathArguments, Type, Variant, parse_macro_input,
+    parse_quote,
 };
 
-#[proc_macro_derive(EasyHash, attributes(easy_hash_ignore))]
+#[proc_macro_derive(EasyHash, attributes(easy_hash_ignore, easy_hash))]
 pub fn derive_easy_hash(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
     let input = parse_macro_input!(input as DeriveInput);
 
+    // Hand the output tokens back to the compiler.
+    match expand_derive(input, cfg!(feature = "tree"), cfg!(feature = "registry")) {
+        Ok(expanded) => proc_macro::TokenStream::from(expanded),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generate the `EasyHash` impl for a parsed derive input. `tree` adds `ehash_tree`,
+/// which only exists with the `tree` feature of easy_hash, and `registry` registers
+/// the salts of non-generic types, which needs its `registry` feature.
+fn expand_derive(input: DeriveInput, tree: bool, registry: bool) -> syn::Result<TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&input)?;
+
     // Used in the quasi-quotation below as `#name`.
     let name = input.ident;
 
-    // Add a bound `T: EasyHash` to every type parameter T.
-    let generics = add_trait_bounds(input.generics);
+    // Add a bound `T: EasyHash` to every type parameter T, or require the whole
+    // type to be `Pod` when hashing its bytes.
+    let generics = match container_attrs.bytes {
+        Some(_) => add_pod_bound(&name, input.generics),
+        None => add_trait_bounds(input.generics),
+    };
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // Generic types have no single salt to register.
+    let registered = registry && generics.params.is_empty();
+
+    if let Some(remote) = &container_attrs.remote {
+        if let Some(group) = groups(&input.data)?.first() {
+            return Err(syn::Error::new(
+                group.span(),
+                "field groups are not supported on remote definitions",
+            ));
+        }
+
+        let target = Target {
+            value: quote! { remote },
+            variant_prefix: remote_variant_prefix(remote)?,
+            mode: Mode::Hash,
+            version: None,
+        };
+        let ehash_fn_inner = hash_sum(&input.data, &target)?;
+        let schema_hash =
+            schema::expand_schema(&remote_name(remote), &input.data, &container_attrs)?;
+
+        let register = registered.then(|| {
+            quote! {
+                easy_hash::registry::inventory::submit! {
+                    easy_hash::registry::SaltEntry::named::<#remote>(
+                        #name::TYPE_SALT,
+                        #name::TYPE_SALT64,
+                    )
+                }
+            }
+        });
 
-    // Generate an expression to sum up the hash of the input
-    let ehash_fn_inner = hash_sum(&input.data);
+        // Like serde's remote derive, the mirror type gets inherent items that hash
+        // the foreign type, for use through `#[easy_hash(with = "...")]`.
+        return Ok(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                pub const TYPE_SALT: u32 = easy_hash::type_salt::<#remote>();
+                pub const TYPE_SALT64: u64 = easy_hash::type_salt64::<#remote>();
+                pub const SCHEMA_HASH: u64 = #schema_hash;
 
-    let expanded = quote! {
+                pub fn ehash(remote: &#remote) -> u64 {
+                    Self::ehash_in::<easy_hash::v1::V1>(remote)
+                }
+
+                pub fn ehash_in<__F: easy_hash::HashFormat>(remote: &#remote) -> u64 {
+                    #ehash_fn_inner
+                }
+            }
+
+            #register
+        });
+    }
+
+    let version = container_attrs
+        .version
+        .as_ref()
+        .map(|version| version.value);
+
+    // Generate the type salt and an expression to sum up the hash of the input
+    let default_type_salt = quote! { easy_hash::type_salt::<#name #ty_generics>() };
+    let expansion = match (container_attrs.transparent, container_attrs.bytes) {
+        (Some(span), _) => expand_transparent(&input.data, span)?,
+        (None, Some(span)) => Expansion {
+            type_salt: default_type_salt,
+            type_salt64: None,
+            ehash: expand_bytes(&input.data, span)?,
+            ehash_group: None,
+            ehash_tree: None,
+        },
+        (None, None) => Expansion {
+            type_salt: default_type_salt,
+            type_salt64: None,
+            ehash: hash_sum(&input.data, &Target::self_().versioned(version))?,
+            ehash_group: expand_groups(&input.data, version)?,
+            ehash_tree: Some(hash_sum(&input.data, &Target::tree().versioned(version))?),
+        },
+    };
+
+    let schema_hash = schema::expand_schema(&name.to_string(), &input.data, &container_attrs)?;
+
+    let Expansion {
+        type_salt,
+        type_salt64,
+        ehash: ehash_fn_inner,
+        ehash_group,
+        ehash_tree,
+    } = expansion;
+    let type_salt64 = type_salt64.map(|type_salt64| {
+        quote! { const TYPE_SALT64: u64 = #type_salt64; }
+    });
+    // Types without field groups keep the default `ehash_group`, which hashes them whole.
+    let ehash_group_fn = ehash_group.map(|ehash_group_fn_inner| {
+        quote! {
+            fn ehash_group(&self, group: &str) -> u64 {
+                #ehash_group_fn_inner
+            }
+        }
+    });
+    let ehash_tree_fn = ehash_tree.filter(|_| tree).map(|ehash_tree_fn_inner| {
+        quote! {
+            fn ehash_tree(&self) -> easy_hash::tree::HashTree {
+                #ehash_tree_fn_inner
+            }
+        }
+    });
+
+    let versioned_impl = container_attrs.version.as_ref().map(|version| {
+        let version = version.value;
+        let previous_versions = container_attrs.previous.iter().map(|p| p.version.value);
+        let previous_hashes = container_attrs.previous.iter().map(|p| &p.hash);
+        quote! {
+            impl #impl_generics easy_hash::EasyHashVersioned for #name #ty_generics #where_clause {
+                const VERSION: u32 = #version;
+
+                fn ehash_as_version(&self, version: u32) -> ::core::option::Option<u64> {
+                    match version {
+                        #version => ::core::option::Option::Some(easy_hash::EasyHash::ehash(self)),
+                        #(#previous_versions => ::core::option::Option::Some(#previous_hashes(self)),)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+
+    let register = registered.then(|| {
+        quote! {
+            easy_hash::registry::inventory::submit! {
+                easy_hash::registry::SaltEntry::of::<#name>()
+            }
+        }
+    });
+
+    Ok(quote! {
         // The generated impl.
         impl #impl_generics easy_hash::EasyHash for #name #ty_generics #where_clause {
-            const TYPE_SALT: u32 = easy_hash::type_salt::<#name #ty_generics>();
+            const TYPE_SALT: u32 = #type_salt;
+            #type_salt64
+            const SCHEMA_HASH: u64 = #schema_hash;
 
             fn ehash(&self) -> u64 {
+                easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+            }
+
+            fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
                 #ehash_fn_inner
             }
+
+            #ehash_group_fn
+
+            #ehash_tree_fn
         }
-    };
 
-    // Hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+        #versioned_impl
+
+        #register
+    })
 }
 
 // Add a bound `T: EasyHash` to every type parameter T.
@@ -49,208 +211,763 @@ fn add_trait_bounds(mut generics: Generics) -> Generics {
     generics
 }
 
+// Require `Self: Pod` instead of bounding each type parameter, since only the
+// bytes of the value are hashed.
+fn add_pod_bound(name: &Ident, mut generics: Generics) -> Generics {
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let predicate = parse_quote!(#name #ty_generics: easy_hash::bytemuck::Pod);
+    generics.make_where_clause().predicates.push(predicate);
+    generics
+}
+
+/// The generated items of an `EasyHash` impl.
+struct Expansion {
+    type_salt: TokenStream,
+    /// The 64-bit salt, if the type does not use the default.
+    type_salt64: Option<TokenStream>,
+    /// Body of `ehash_in`, generic over the format `__F`.
+    ehash: TokenStream,
+    /// Body of `ehash_group`, if the type overrides the default.
+    ehash_group: Option<TokenStream>,
+    /// Body of `ehash_tree`, if the type overrides the default.
+    ehash_tree: Option<TokenStream>,
+}
+
+/// Which trait method a generated body implements.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// `ehash_in`, in the format `__F`.
+    Hash,
+    /// `ehash_group`, which hashes only the fields tagged with its `group` argument.
+    Group,
+    /// `ehash_tree`, which hashes like `ehash` and records a node per field.
+    ///
+    /// Groups and trees always use the v1 format.
+    Tree,
+}
+
+/// How a generated hash body refers to the value being hashed.
+struct Target {
+    /// Expression for the value: `self`, or the argument of a remote hash function.
+    value: TokenStream,
+    /// Path that enum variants are named through: `Self`, or the remote enum.
+    variant_prefix: TokenStream,
+    mode: Mode,
+    /// The version declared with `#[easy_hash(version = N)]`, hashed before anything else.
+    version: Option<u32>,
+}
+
+impl Target {
+    fn self_() -> Self {
+        Target {
+            value: quote! { self },
+            variant_prefix: quote! { Self },
+            mode: Mode::Hash,
+            version: None,
+        }
+    }
+
+    fn group() -> Self {
+        Target {
+            mode: Mode::Group,
+            ..Target::self_()
+        }
+    }
+
+    fn tree() -> Self {
+        Target {
+            mode: Mode::Tree,
+            ..Target::self_()
+        }
+    }
+
+    fn versioned(self, version: Option<u32>) -> Self {
+        Target { version, ..self }
+    }
+
+    /// Whether a field with these attributes can contribute to the hash.
+    fn hashes(&self, attrs: &FieldAttrs) -> bool {
+        !attrs.ignore && (self.mode != Mode::Group || !attrs.groups.is_empty())
+    }
+
+    /// Generate the statements that start a hash body.
+    fn begin(&self) -> TokenStream {
+        let children = (self.mode == Mode::Tree).then(|| {
+            quote! { let mut children = easy_hash::tree::Children::new(); }
+        });
+        let version = self.version.map(|version| {
+            quote! { checksum.update(&[#version]); }
+        });
+        quote! {
+            let mut checksum = easy_hash::fletcher::Fletcher64::new();
+            #version
+            #children
+        }
+    }
+
+    /// Generate the statement feeding the type salt, followed by the index of the
+    /// hashed enum variant if there is one, into `checksum`.
+    fn update_salt(&self, variant_index: Option<&Index>) -> TokenStream {
+        let variant_index = variant_index.into_iter();
+        match self.mode {
+            Mode::Hash => quote! {
+                __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                #(checksum.update(&[#variant_index]);)*
+            },
+            Mode::Group | Mode::Tree => quote! {
+                checksum.update(&[Self::TYPE_SALT #(, #variant_index)*]);
+            },
+        }
+    }
+
+    /// Generate the expression that a hash body returns.
+    fn finish(&self) -> TokenStream {
+        match self.mode {
+            Mode::Hash => quote! { __F::finish(checksum.value()) },
+            Mode::Group => quote! { checksum.value() },
+            Mode::Tree => quote! {
+                easy_hash::tree::HashTree::new(
+                    ::core::any::type_name::<Self>(),
+                    Self::TYPE_SALT,
+                    checksum.value(),
+                )
+                .with_children(children)
+            },
+        }
+    }
+}
+
+/// Collect the groups named by the `group = "..."` options of all fields, in order of
+/// first appearance.
+fn groups(data: &Data) -> syn::Result<Vec<LitStr>> {
+    let fields: Vec<&Field> = match data {
+        Data::Struct(data_struct) => data_struct.fields.iter().collect(),
+        Data::Enum(data_enum) => data_enum.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(data_union) => data_union.fields.named.iter().collect(),
+    };
+    let mut groups: Vec<LitStr> = Vec::new();
+    for (_, attrs) in parse_fields(fields)? {
+        for group in attrs.groups {
+            if !groups.iter().any(|g| g.value() == group.value()) {
+                groups.push(group);
+            }
+        }
+    }
+    Ok(groups)
+}
+
+/// Generate the body of `ehash_group`, or `None` if no field is in a group.
+///
+/// A type only filters its fields for the groups it names itself. Any other group
+/// hashes it whole, so tagging a field with a group includes all of the field
+/// unless its type splits that group further.
+fn expand_groups(data: &Data, version: Option<u32>) -> syn::Result<Option<TokenStream>> {
+    let groups = groups(data)?;
+    if groups.is_empty() {
+        return Ok(None);
+    }
+    let ehash_fn_inner = hash_sum(data, &Target::group().versioned(version))?;
+
+    Ok(Some(quote! {
+        if !matches!(group, #(#groups)|*) {
+            return easy_hash::EasyHash::ehash(self);
+        }
+        #ehash_fn_inner
+    }))
+}
+
+/// The name of a remote type, without its path or generic arguments.
+fn remote_name(remote: &Type) -> String {
+    match remote {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|last| last.ident.to_string())
+            .unwrap_or_default(),
+        _ => quote!(#remote).to_string(),
+    }
+}
+
+/// Name the variants of a remote enum through its path, without generic arguments.
+fn remote_variant_prefix(remote: &Type) -> syn::Result<TokenStream> {
+    let Type::Path(type_path) = remote else {
+        return Err(syn::Error::new_spanned(
+            remote,
+            "`remote` must name a struct or enum",
+        ));
+    };
+    let mut path = type_path.path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+    Ok(quote! { #path })
+}
+
 // Generate an expression to sum up the hashes of each field.
-fn hash_sum(data: &Data) -> TokenStream {
+fn hash_sum(data: &Data, target: &Target) -> syn::Result<TokenStream> {
     match *data {
-        Data::Enum(ref data_enum) => expand_enum(data_enum),
-        Data::Struct(ref data_struct) => expand_struct(data_struct),
-        Data::Union(_) => unimplemented!(),
+        Data::Enum(ref data_enum) => expand_enum(data_enum, target),
+        Data::Struct(ref data_struct) => expand_struct(data_struct, target),
+        Data::Union(ref data_union) => Err(syn::Error::new(
+            data_union.union_token.span,
+            "EasyHash cannot be derived for unions; add #[easy_hash(bytes)] to hash a `bytemuck::Pod` union by its bytes",
+        )),
     }
 }
 
 /// Generate the hash implementation for an enum.
 /// Creates a match expression that hashes the type salt, variant index, and variant fields.
-fn expand_enum(data_enum: &DataEnum) -> TokenStream {
+fn expand_enum(data_enum: &DataEnum, target: &Target) -> syn::Result<TokenStream> {
     let match_arms = data_enum
         .variants
         .iter()
         .enumerate()
-        .map(|(variant_index, variant)| expand_enum_variant(variant_index, variant));
+        .map(|(variant_index, variant)| expand_enum_variant(variant_index, variant, target))
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    quote! {
-        let mut checksum = easy_hash::fletcher::Fletcher64::new();
-        match self {
+    let value = &target.value;
+    let begin = target.begin();
+    let finish = match target.mode {
+        Mode::Hash | Mode::Group => target.finish(),
+        // The hashed variant gets a node of its own, holding the nodes of its fields.
+        Mode::Tree => {
+            let prefix = &target.variant_prefix;
+            let variant_idents = data_enum.variants.iter().map(|v| &v.ident);
+            let variant_names = data_enum.variants.iter().map(|v| v.ident.to_string());
+            quote! {
+                let variant = match #value {
+                    #(#prefix::#variant_idents { .. } => #variant_names,)*
+                };
+                let node = easy_hash::tree::HashTree::new(
+                    ::core::any::type_name::<Self>(),
+                    Self::TYPE_SALT,
+                    checksum.value(),
+                );
+                node.clone()
+                    .with_children(easy_hash::tree::Children::from([
+                        node.with_name(variant).with_children(children),
+                    ]))
+            }
+        }
+    };
+
+    Ok(quote! {
+        #begin
+        match #value {
             #(#match_arms)*
         }
-        checksum.value()
-    }
+        #finish
+    })
 }
 
 /// Generate a match arm for a single enum variant.
-fn expand_enum_variant(variant_index: usize, variant: &Variant) -> TokenStream {
+fn expand_enum_variant(
+    variant_index: usize,
+    variant: &Variant,
+    target: &Target,
+) -> syn::Result<TokenStream> {
+    attr::reject_variant_attrs(variant)?;
+
     let enum_variant_index = Index::from(variant_index);
     let variant_ident = &variant.ident;
+    let prefix = &target.variant_prefix;
+    let variant_path = quote! { #prefix::#variant_ident };
 
     match &variant.fields {
-        Fields::Unit => expand_enum_variant_unit(variant_ident, enum_variant_index),
+        Fields::Unit => Ok(expand_enum_variant_unit(
+            &variant_path,
+            enum_variant_index,
+            target,
+        )),
         Fields::Unnamed(fields) => {
-            expand_enum_variant_unnamed(variant_ident, enum_variant_index, fields)
+            expand_enum_variant_unnamed(&variant_path, enum_variant_index, fields, target)
         }
         Fields::Named(fields) => {
-            expand_enum_variant_named(variant_ident, enum_variant_index, fields)
+            expand_enum_variant_named(&variant_path, enum_variant_index, fields, target)
         }
     }
 }
 
 /// Generate a match arm for a unit enum variant.
 /// Hashes only the type salt and variant index.
-fn expand_enum_variant_unit(variant_ident: &Ident, variant_index: Index) -> TokenStream {
+fn expand_enum_variant_unit(
+    variant_path: &TokenStream,
+    variant_index: Index,
+    target: &Target,
+) -> TokenStream {
+    let update_salt = target.update_salt(Some(&variant_index));
     quote! {
-        Self::#variant_ident => {
-            checksum.update(&[Self::TYPE_SALT, #variant_index]);
+        #variant_path => {
+            #update_salt
         }
     }
 }
 
 /// Generate a match arm for an unnamed fields enum variant (tuple variant).
-/// Hashes the type salt, variant index, and all tuple fields.
+/// Hashes the type salt, variant index, and all tuple fields (respecting `#[easy_hash_ignore]`).
 fn expand_enum_variant_unnamed(
-    variant_ident: &Ident,
+    variant_path: &TokenStream,
     variant_index: Index,
     fields: &FieldsUnnamed,
-) -> TokenStream {
-    let field_names = fields.unnamed.iter().enumerate().map(|(i, f)| {
-        let name = Ident::new(&format!("f{}", i), f.span());
-        quote_spanned! {f.span()=> #name }
-    });
+    target: &Target,
+) -> syn::Result<TokenStream> {
+    let fields = parse_fields(&fields.unnamed)?;
 
-    let field_hash_exprs = fields.unnamed.iter().enumerate().map(|(i, f)| {
-        let name = Ident::new(&format!("f{}", i), f.span());
-        quote_spanned! {f.span()=>
-            easy_hash::EasyHash::ehash(#name)
+    let field_names = fields.iter().enumerate().map(|(i, (f, attrs))| {
+        if !target.hashes(attrs) {
+            quote_spanned! {f.span()=> _ }
+        } else {
+            let name = Ident::new(&format!("f{}", i), f.span());
+            quote_spanned! {f.span()=> #name }
         }
     });
 
-    quote! {
-        Self::#variant_ident(#(#field_names,)*) => {
-            checksum.update(&[Self::TYPE_SALT, #variant_index]);
-            checksum.update(&easy_hash::u64_to_u32_slice(&[#(#field_hash_exprs,)*]));
+    let hashed_fields = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, attrs))| target.hashes(attrs))
+        .map(|(i, (f, attrs))| {
+            let name = Ident::new(&format!("f{}", i), f.span());
+            HashedField::new(f, attrs, i.to_string(), quote! { #name })
+        })
+        .collect::<Vec<_>>();
+    let update_fields = update_fields(&hashed_fields, target);
+    let update_salt = target.update_salt(Some(&variant_index));
+
+    Ok(quote! {
+        #variant_path(#(#field_names,)*) => {
+            #update_salt
+            #update_fields
         }
-    }
+    })
 }
 
 /// Generate a match arm for a named fields enum variant.
 /// Hashes the type salt, variant index, and all named fields (respecting `#[easy_hash_ignore]`).
 fn expand_enum_variant_named(
-    variant_ident: &Ident,
+    variant_path: &TokenStream,
     variant_index: Index,
     fields: &FieldsNamed,
-) -> TokenStream {
+    target: &Target,
+) -> syn::Result<TokenStream> {
+    let fields = parse_fields(&fields.named)?;
+
     let field_names = fields
-        .named
         .iter()
-        .filter(|f| !has_easy_hash_ignore_attr(f))
-        .map(|f| {
+        .filter(|(_, attrs)| target.hashes(attrs))
+        .map(|(f, _)| {
             let name = &f.ident;
             quote_spanned! {f.span()=> #name }
         });
+    // Ignored fields are not bound, so the pattern has to allow for them.
+    let rest = fields
+        .iter()
+        .any(|(_, attrs)| !target.hashes(attrs))
+        .then(|| quote! { .. });
 
-    let field_hash_exprs = fields
-        .named
+    let hashed_fields = fields
         .iter()
-        .filter(|f| !has_easy_hash_ignore_attr(f))
-        .map(|f| {
+        .filter(|(_, attrs)| target.hashes(attrs))
+        .map(|(f, attrs)| {
             let name = &f.ident;
-            quote_spanned! {f.span()=> #name.ehash() }
-        });
+            HashedField::new(f, attrs, field_name(f), quote! { #name })
+        })
+        .collect::<Vec<_>>();
+    let update_fields = update_fields(&hashed_fields, target);
+    let update_salt = target.update_salt(Some(&variant_index));
 
-    quote! {
-        Self::#variant_ident { #(#field_names,)* } => {
-            checksum.update(&[Self::TYPE_SALT, #variant_index]);
-            checksum.update(&easy_hash::u64_to_u32_slice(&[#(#field_hash_exprs,)*]));
+    Ok(quote! {
+        #variant_path { #(#field_names,)* #rest } => {
+            #update_salt
+            #update_fields
         }
-    }
+    })
 }
 
 /// Generate the hash implementation for a struct.
-fn expand_struct(data_struct: &DataStruct) -> TokenStream {
+fn expand_struct(data_struct: &DataStruct, target: &Target) -> syn::Result<TokenStream> {
     match &data_struct.fields {
-        Fields::Named(fields) => expand_struct_named(fields),
-        Fields::Unnamed(fields) => expand_struct_unnamed(fields),
-        Fields::Unit => expand_struct_unit(),
+        Fields::Named(fields) => expand_struct_named(fields, target),
+        Fields::Unnamed(fields) => expand_struct_unnamed(fields, target),
+        Fields::Unit => Ok(expand_struct_unit(target)),
     }
 }
 
 /// Generate the hash implementation for a struct with named fields.
 /// Hashes the type salt and all fields (respecting `#[easy_hash_ignore]`).
-fn expand_struct_named(fields: &FieldsNamed) -> TokenStream {
-    let field_hash_exprs = fields
-        .named
+fn expand_struct_named(fields: &FieldsNamed, target: &Target) -> syn::Result<TokenStream> {
+    let fields = parse_fields(&fields.named)?;
+    let value = &target.value;
+
+    let hashed_fields = fields
         .iter()
-        .filter(|f| !has_easy_hash_ignore_attr(f))
-        .map(|f| {
+        .filter(|(_, attrs)| !attrs.ignore)
+        .map(|(f, attrs)| {
             let name = &f.ident;
-            quote_spanned! {f.span()=>
-                easy_hash::EasyHash::ehash(&self.#name)
-            }
-        });
+            HashedField::new(f, attrs, field_name(f), quote! { &#value.#name })
+        })
+        .collect::<Vec<_>>();
+    let update_fields = update_fields(&hashed_fields, target);
+    let begin = target.begin();
+    let update_salt = target.update_salt(None);
+    let finish = target.finish();
 
-    quote! {
-        let mut checksum = easy_hash::fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
-        checksum.update(&easy_hash::u64_to_u32_slice(&[ #(#field_hash_exprs,)* ]));
-        checksum.value()
-    }
+    Ok(quote! {
+        #begin
+        #update_salt
+        #update_fields
+        #finish
+    })
 }
 
 /// Generate the hash implementation for a struct with unnamed fields (tuple struct).
-/// Hashes the type salt and all tuple fields.
-fn expand_struct_unnamed(fields: &FieldsUnnamed) -> TokenStream {
-    let field_hash_exprs = fields.unnamed.iter().enumerate().map(|(i, f)| {
-        let index = Index::from(i);
-        quote_spanned! {f.span()=>
-            easy_hash::EasyHash::ehash(&self.#index)
-        }
-    });
+/// Hashes the type salt and all tuple fields (respecting `#[easy_hash_ignore]`).
+fn expand_struct_unnamed(fields: &FieldsUnnamed, target: &Target) -> syn::Result<TokenStream> {
+    let fields = parse_fields(&fields.unnamed)?;
+    let value = &target.value;
 
-    quote! {
-        let mut checksum = easy_hash::fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
-        checksum.update(&easy_hash::u64_to_u32_slice(&[ #(#field_hash_exprs,)* ]));
-        checksum.value()
-    }
+    let hashed_fields = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, attrs))| !attrs.ignore)
+        .map(|(i, (f, attrs))| {
+            let index = Index::from(i);
+            HashedField::new(f, attrs, i.to_string(), quote! { &#value.#index })
+        })
+        .collect::<Vec<_>>();
+    let update_fields = update_fields(&hashed_fields, target);
+    let begin = target.begin();
+    let update_salt = target.update_salt(None);
+    let finish = target.finish();
+
+    Ok(quote! {
+        #begin
+        #update_salt
+        #update_fields
+        #finish
+    })
 }
 
 /// Generate the hash implementation for a unit struct.
 /// Hashes only the type salt.
-fn expand_struct_unit() -> TokenStream {
+fn expand_struct_unit(target: &Target) -> TokenStream {
+    let begin = target.begin();
+    let update_salt = target.update_salt(None);
+    let finish = target.finish();
     quote! {
-        let mut checksum = easy_hash::fletcher::Fletcher64::new();
-        checksum.update(&[Self::TYPE_SALT]);
-        checksum.value()
+        #begin
+        #update_salt
+        #finish
     }
 }
 
-/// Check if a field has the `#[easy_hash_ignore]` attribute.
-fn has_easy_hash_ignore_attr(field: &syn::Field) -> bool {
-    field
-        .attrs
+/// Generate the type salt and hash implementation for a `#[easy_hash(transparent)]` type,
+/// both of which are forwarded to its single non-ignored field.
+fn expand_transparent(data: &Data, span: Span) -> syn::Result<Expansion> {
+    let Data::Struct(data_struct) = data else {
+        return Err(syn::Error::new(
+            span,
+            "#[easy_hash(transparent)] is only supported on structs",
+        ));
+    };
+
+    let fields = parse_fields(&data_struct.fields)?;
+    let mut hashed = fields
         .iter()
-        .any(|attr| attr.path().is_ident("easy_hash_ignore"))
+        .enumerate()
+        .filter(|(_, (_, attrs))| !attrs.ignore);
+    let (index, (field, attrs)) = match (hashed.next(), hashed.next()) {
+        (Some(only), None) => only,
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "#[easy_hash(transparent)] requires exactly one non-ignored field",
+            ));
+        }
+    };
+
+    if attrs.skip_if.is_some() {
+        return Err(syn::Error::new(
+            field.span(),
+            "the field of a #[easy_hash(transparent)] type cannot be skipped",
+        ));
+    }
+    if let Some(group) = attrs.groups.first() {
+        return Err(syn::Error::new(
+            group.span(),
+            "the field of a #[easy_hash(transparent)] type cannot be put in a group; it is hashed in every group",
+        ));
+    }
+
+    let ty = &field.ty;
+    let member = match &field.ident {
+        Some(ident) => quote! { #ident },
+        None => {
+            let index = Index::from(index);
+            quote! { #index }
+        }
+    };
+
+    if let Some(quantize) = &attrs.quantize {
+        let quantization = quantize.to_tokens();
+        return Ok(Expansion {
+            type_salt: quote! { <#ty as easy_hash::EasyHash>::TYPE_SALT },
+            type_salt64: Some(quote! { <#ty as easy_hash::EasyHash>::TYPE_SALT64 }),
+            ehash: quote_spanned! {field.span()=>
+                __F::finish(easy_hash::EasyHashQuantized::ehash_quantized(&self.#member, #quantization))
+            },
+            ehash_group: None,
+            ehash_tree: None,
+        });
+    }
+
+    match &attrs.with {
+        Some(with) => Ok(Expansion {
+            type_salt: quote! { #with::TYPE_SALT },
+            type_salt64: Some(quote! { #with::TYPE_SALT64 }),
+            ehash: quote_spanned! {with.span()=> #with::ehash_in::<__F>(&self.#member) },
+            ehash_group: None,
+            ehash_tree: None,
+        }),
+        // Groups and trees are forwarded too, so wrapping a type does not hide its parts.
+        None => Ok(Expansion {
+            type_salt: quote! { <#ty as easy_hash::EasyHash>::TYPE_SALT },
+            type_salt64: Some(quote! { <#ty as easy_hash::EasyHash>::TYPE_SALT64 }),
+            ehash: quote_spanned! {field.span()=>
+                easy_hash::EasyHash::ehash_in::<__F>(&self.#member)
+            },
+            ehash_group: Some(quote_spanned! {field.span()=>
+                easy_hash::EasyHash::ehash_group(&self.#member, group)
+            }),
+            ehash_tree: Some(quote_spanned! {field.span()=>
+                easy_hash::EasyHash::ehash_tree(&self.#member)
+            }),
+        }),
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use pretty_assertions::assert_eq;
-    use syn::parse_quote;
+/// Generate the hash implementation for a `#[easy_hash(bytes)]` type, which hashes the
+/// value's byte representation the same way as a slice of `Pod` values.
+fn expand_bytes(data: &Data, span: Span) -> syn::Result<TokenStream> {
+    let fields: Vec<&Field> = match data {
+        Data::Struct(data_struct) => data_struct.fields.iter().collect(),
+        Data::Union(data_union) => data_union.fields.named.iter().collect(),
+        Data::Enum(_) => {
+            return Err(syn::Error::new(
+                span,
+                "#[easy_hash(bytes)] is only supported on structs and unions",
+            ));
+        }
+    };
 
-    fn expand_as_string(input: DeriveInput) -> String {
-        let name = input.ident;
-        let generics = add_trait_bounds(input.generics);
-        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-        let ehash_fn_inner = hash_sum(&input.data);
+    for (field, attrs) in parse_fields(fields)? {
+        if attrs.ignore
+            || attrs.skip_if.is_some()
+            || attrs.quantize.is_some()
+            || !attrs.groups.is_empty()
+        {
+            return Err(syn::Error::new(
+                field.span(),
+                "field options have no effect on a #[easy_hash(bytes)] type, which hashes all of its bytes",
+            ));
+        }
+    }
+
+    Ok(quote! {
+        easy_hash::bytemuck_slices::ehash_pod_slice_in::<__F, Self>(
+            Self::TYPE_SALT,
+            Self::TYPE_SALT64,
+            ::core::slice::from_ref(self),
+        )
+    })
+}
+
+/// Parse the attributes of each field, keeping the fields in declaration order.
+fn parse_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> syn::Result<Vec<(&'a Field, FieldAttrs)>> {
+    fields
+        .into_iter()
+        .map(|f| Ok((f, FieldAttrs::from_field(f)?)))
+        .collect()
+}
+
+/// A field that contributes to the hash of its parent.
+struct HashedField<'a> {
+    field: &'a Field,
+    /// Name of the field's node in a `HashTree`: its identifier or tuple index.
+    name: String,
+    /// Expression evaluating to a reference to the field.
+    access: TokenStream,
+    /// Expression evaluating to the field's hash in the v1 format.
+    hash: TokenStream,
+    /// Expression evaluating to the field's hash in the format `__F`.
+    format_hash: TokenStream,
+    attrs: &'a FieldAttrs,
+}
+
+impl<'a> HashedField<'a> {
+    /// The field is hashed through `EasyHash` unless it names a remote definition with
+    /// `with` or asks for its floats to be quantized.
+    fn new(field: &'a Field, attrs: &'a FieldAttrs, name: String, access: TokenStream) -> Self {
+        let (hash, format_hash) = match (&attrs.with, &attrs.quantize) {
+            (Some(with), _) => (
+                quote_spanned! {with.span()=> #with::ehash(#access) },
+                quote_spanned! {with.span()=> #with::ehash_in::<__F>(#access) },
+            ),
+            (None, Some(quantize)) => {
+                let quantization = quantize.to_tokens();
+                let hash = quote_spanned! {field.span()=>
+                    easy_hash::EasyHashQuantized::ehash_quantized(#access, #quantization)
+                };
+                let format_hash = quote! { __F::finish(#hash) };
+                (hash, format_hash)
+            }
+            (None, None) => (
+                quote_spanned! {field.span()=> easy_hash::EasyHash::ehash(#access) },
+                quote_spanned! {field.span()=> easy_hash::EasyHash::ehash_in::<__F>(#access) },
+            ),
+        };
+        HashedField {
+            field,
+            name,
+            access,
+            hash,
+            format_hash,
+            attrs,
+        }
+    }
+
+    /// Expression evaluating to the field's hash in the method that `target` implements.
+    fn hash_in(&self, target: &Target) -> TokenStream {
+        match target.mode {
+            Mode::Hash => self.format_hash.clone(),
+            Mode::Group => self.group_hash(),
+            Mode::Tree => self.tree_hash(),
+        }
+    }
+
+    /// Expression evaluating to the field's hash within the group named by `group`,
+    /// which recurses into the field unless it is hashed in a custom way.
+    fn group_hash(&self) -> TokenStream {
+        if self.attrs.with.is_some() || self.attrs.quantize.is_some() {
+            return self.hash.clone();
+        }
+        let access = &self.access;
+        quote_spanned! {self.field.span()=>
+            easy_hash::EasyHash::ehash_group(#access, group)
+        }
+    }
 
+    /// Expression evaluating to the field's hash that also adds the field's node to
+    /// `children`. Fields hashed in a custom way become leaves.
+    fn tree_hash(&self) -> TokenStream {
+        let ty = &self.field.ty;
+        let hash = &self.hash;
+        let node = match (&self.attrs.with, &self.attrs.quantize) {
+            (Some(with), _) => quote! {
+                easy_hash::tree::HashTree::new(::core::any::type_name::<#ty>(), #with::TYPE_SALT, #hash)
+            },
+            (None, Some(_)) => quote! {
+                easy_hash::tree::HashTree::new(
+                    ::core::any::type_name::<#ty>(),
+                    <#ty as easy_hash::EasyHash>::TYPE_SALT,
+                    #hash,
+                )
+            },
+            (None, None) => {
+                let access = &self.access;
+                quote_spanned! {self.field.span()=>
+                    easy_hash::EasyHash::ehash_tree(#access)
+                }
+            }
+        };
+
+        let name = &self.name;
         quote! {
-            impl #impl_generics easy_hash::EasyHash for #name #ty_generics #where_clause {
-                const TYPE_SALT: u32 = easy_hash::type_salt::<#name #ty_generics>();
+            {
+                let node = #node.with_name(#name);
+                let hash = node.hash;
+                children.push(node);
+                hash
+            }
+        }
+    }
+}
 
-                fn ehash(&self) -> u64 {
-                    #ehash_fn_inner
+/// The name of a named field, as a string.
+fn field_name(field: &Field) -> String {
+    field
+        .ident
+        .as_ref()
+        .map(Ident::to_string)
+        .unwrap_or_default()
+}
+
+/// Generate the statements feeding the hashes of `fields` into `checksum`.
+///
+/// When some fields are conditionally skipped, the hashes of the remaining fields
+/// are gathered into a buffer first, so that a skipped field leaves exactly the
+/// same checksum input as a struct that never had the field. Fields outside the
+/// hashed group are skipped the same way.
+fn update_fields(fields: &[HashedField], target: &Target) -> TokenStream {
+    if target.mode != Mode::Group && fields.iter().all(|f| f.attrs.skip_if.is_none()) {
+        let field_hash_exprs = fields.iter().map(|f| f.hash_in(target));
+        return quote! {
+            checksum.update(&easy_hash::u64_to_u32_slice(&[ #(#field_hash_exprs,)* ]));
+        };
+    }
+
+    let fields: Vec<&HashedField> = fields.iter().filter(|f| target.hashes(f.attrs)).collect();
+    if fields.is_empty() {
+        return TokenStream::new();
+    }
+
+    let capacity = fields.len();
+    let pushes = fields.iter().map(|f| {
+        let hash = f.hash_in(target);
+        let push = quote! {
+            hashes[len] = #hash;
+            len += 1;
+        };
+
+        let mut conditions = Vec::new();
+        if target.mode == Mode::Group {
+            conditions.push(f.attrs.in_group(&quote! { group }));
+        }
+        if let Some(skip_if) = &f.attrs.skip_if {
+            let condition = skip_if.condition(f.field, &f.access);
+            conditions.push(quote! { !(#condition) });
+        }
+
+        if conditions.is_empty() {
+            push
+        } else {
+            quote! {
+                if #(#conditions)&&* {
+                    #push
                 }
             }
         }
-        .to_string()
+    });
+
+    quote! {
+        let mut hashes = [0u64; #capacity];
+        let mut len = 0usize;
+        #(#pushes)*
+        checksum.update(&easy_hash::u64_to_u32_slice(&hashes[..len]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use syn::parse_quote;
+
+    fn expand_as_string(input: DeriveInput) -> String {
+        expand_derive(input, false, false).unwrap().to_string()
     }
 
     #[test]
@@ -267,14 +984,103 @@ mod tests {
         let expected = quote! {
             impl<T: easy_hash::EasyHash> easy_hash::EasyHash for Example<T> {
                 const TYPE_SALT: u32 = easy_hash::type_salt::<Example<T> >();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Example {}",
+                    [
+                        easy_hash::schema::compose("a", [easy_hash::schema::of::<u32>()]),
+                        easy_hash::schema::compose("b #[easy_hash_ignore]", [easy_hash::schema::hash_str("T")])
+                    ]
+                );
 
                 fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
                     let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    checksum.update(&easy_hash::u64_to_u32_slice(&[
+                        easy_hash::EasyHash::ehash_in::<__F>(&self.a),
+                    ]));
+                    __F::finish(checksum.value())
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tree_records_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Example {
+                a: u32,
+                #[easy_hash(quantize = 0.5)]
+                b: f32,
+            }
+        };
+
+        let actual = expand_derive(input, true, false).unwrap().to_string();
+        let expected = quote! {
+            impl easy_hash::EasyHash for Example {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Example>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Example {}",
+                    [
+                        easy_hash::schema::compose("a", [easy_hash::schema::of::<u32>()]),
+                        easy_hash::schema::compose(
+                            "b #[easy_hash(quantize = 0.5)]",
+                            [
+                                easy_hash::schema::of::<f32>()
+                            ]
+                        )
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    checksum.update(&easy_hash::u64_to_u32_slice(&[
+                        easy_hash::EasyHash::ehash_in::<__F>(&self.a),
+                        __F::finish(easy_hash::EasyHashQuantized::ehash_quantized(&self.b, easy_hash::Quantization::Step(0.5f64))),
+                    ]));
+                    __F::finish(checksum.value())
+                }
+
+                fn ehash_tree(&self) -> easy_hash::tree::HashTree {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    let mut children = easy_hash::tree::Children::new();
                     checksum.update(&[Self::TYPE_SALT]);
                     checksum.update(&easy_hash::u64_to_u32_slice(&[
-                        easy_hash::EasyHash::ehash(&self.a),
+                        {
+                            let node = easy_hash::EasyHash::ehash_tree(&self.a).with_name("a");
+                            let hash = node.hash;
+                            children.push(node);
+                            hash
+                        },
+                        {
+                            let node = easy_hash::tree::HashTree::new(
+                                ::core::any::type_name::<f32>(),
+                                <f32 as easy_hash::EasyHash>::TYPE_SALT,
+                                easy_hash::EasyHashQuantized::ehash_quantized(&self.b, easy_hash::Quantization::Step(0.5f64)),
+                            )
+                            .with_name("b");
+                            let hash = node.hash;
+                            children.push(node);
+                            hash
+                        },
                     ]));
-                    checksum.value()
+                    easy_hash::tree::HashTree::new(
+                        ::core::any::type_name::<Self>(),
+                        Self::TYPE_SALT,
+                        checksum.value(),
+                    )
+                    .with_children(children)
                 }
             }
         }
@@ -320,28 +1126,60 @@ mod tests {
         let expected = quote! {
             impl easy_hash::EasyHash for Example {
                 const TYPE_SALT: u32 = easy_hash::type_salt::<Example>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "enum Example",
+                    [
+                        easy_hash::schema::compose("Unit", []),
+                        easy_hash::schema::compose(
+                            "Tuple()",
+                            [
+                                easy_hash::schema::compose("0", [easy_hash::schema::of::<u32>()]),
+                                easy_hash::schema::compose("1", [easy_hash::schema::of::<u64>()])
+                            ]
+                        ),
+                        easy_hash::schema::compose(
+                            "Named {}",
+                            [
+                                easy_hash::schema::compose("x", [easy_hash::schema::of::<u8>()]),
+                                easy_hash::schema::compose(
+                                    "y #[easy_hash_ignore]",
+                                    [
+                                        easy_hash::schema::hash_str("u16")
+                                    ]
+                                )
+                            ]
+                        )
+                    ]
+                );
 
                 fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
                     let mut checksum = easy_hash::fletcher::Fletcher64::new();
                     match self {
                         Self::Unit => {
-                            checksum.update(&[Self::TYPE_SALT, 0]);
+                            __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                            checksum.update(&[0]);
                         }
                         Self::Tuple(f0, f1,) => {
-                            checksum.update(&[Self::TYPE_SALT, 1]);
+                            __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                            checksum.update(&[1]);
                             checksum.update(&easy_hash::u64_to_u32_slice(&[
-                                easy_hash::EasyHash::ehash(f0),
-                                easy_hash::EasyHash::ehash(f1),
+                                easy_hash::EasyHash::ehash_in::<__F>(f0),
+                                easy_hash::EasyHash::ehash_in::<__F>(f1),
                             ]));
                         }
-                        Self::Named { x, } => {
-                            checksum.update(&[Self::TYPE_SALT, 2]);
+                        Self::Named { x, .. } => {
+                            __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                            checksum.update(&[2]);
                             checksum.update(&easy_hash::u64_to_u32_slice(&[
-                                x.ehash(),
+                                easy_hash::EasyHash::ehash_in::<__F>(x),
                             ]));
                         }
                     }
-                    checksum.value()
+                    __F::finish(checksum.value())
                 }
             }
         }
@@ -360,14 +1198,239 @@ mod tests {
         let expected = quote! {
             impl easy_hash::EasyHash for Wrapper {
                 const TYPE_SALT: u32 = easy_hash::type_salt::<Wrapper>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Wrapper()",
+                    [
+                        easy_hash::schema::compose("0", [easy_hash::schema::of::<u8>()]),
+                        easy_hash::schema::compose("1", [easy_hash::schema::of::<u16>()])
+                    ]
+                );
 
                 fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
                     let mut checksum = easy_hash::fletcher::Fletcher64::new();
-                    checksum.update(&[Self::TYPE_SALT]);
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    checksum.update(&easy_hash::u64_to_u32_slice(&[
+                        easy_hash::EasyHash::ehash_in::<__F>(&self.0),
+                        easy_hash::EasyHash::ehash_in::<__F>(&self.1),
+                    ]));
+                    __F::finish(checksum.value())
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_struct_skip_if_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Example {
+                a: u32,
+                #[easy_hash(skip_if_default)]
+                b: u8,
+                #[easy_hash(skip_if = "Option::is_none")]
+                c: Option<u8>,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Example {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Example>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Example {}",
+                    [
+                        easy_hash::schema::compose("a", [easy_hash::schema::of::<u32>()]),
+                        easy_hash::schema::compose(
+                            "b #[easy_hash(skip_if_default)]",
+                            [
+                                easy_hash::schema::of::<u8>()
+                            ]
+                        ),
+                        easy_hash::schema::compose(
+                            "c #[easy_hash(skip_if = \"Option::is_none\")]",
+                            [
+                                easy_hash::schema::of::<Option<u8> >()
+                            ]
+                        )
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    let mut hashes = [0u64; 3usize];
+                    let mut len = 0usize;
+                    hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.a);
+                    len += 1;
+                    if !(*&self.b == <u8 as ::core::default::Default>::default()) {
+                        hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.b);
+                        len += 1;
+                    }
+                    if !(Option::is_none(&self.c)) {
+                        hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.c);
+                        len += 1;
+                    }
+                    checksum.update(&easy_hash::u64_to_u32_slice(&hashes[..len]));
+                    __F::finish(checksum.value())
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_quantized_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Body {
+                #[easy_hash(quantize = 0.5)]
+                x: f32,
+                #[easy_hash(ulps = 4)]
+                y: f64,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Body {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Body>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Body {}",
+                    [
+                        easy_hash::schema::compose(
+                            "x #[easy_hash(quantize = 0.5)]",
+                            [
+                                easy_hash::schema::of::<f32>()
+                            ]
+                        ),
+                        easy_hash::schema::compose(
+                            "y #[easy_hash(ulps = 4)]",
+                            [
+                                easy_hash::schema::of::<f64>()
+                            ]
+                        )
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
                     checksum.update(&easy_hash::u64_to_u32_slice(&[
-                        easy_hash::EasyHash::ehash(&self.0),
-                        easy_hash::EasyHash::ehash(&self.1),
+                        __F::finish(easy_hash::EasyHashQuantized::ehash_quantized(&self.x, easy_hash::Quantization::Step(0.5f64))),
+                        __F::finish(easy_hash::EasyHashQuantized::ehash_quantized(&self.y, easy_hash::Quantization::Ulps(4u32))),
                     ]));
+                    __F::finish(checksum.value())
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_quantize_rejects_non_positive_step() {
+        let input: DeriveInput = parse_quote! {
+            struct Body {
+                #[easy_hash(quantize = 0.0)]
+                x: f32,
+            }
+        };
+
+        let err = expand_derive(input, false, false).unwrap_err().to_string();
+        assert_eq!(err, "`quantize` must be a positive, finite step");
+    }
+
+    #[test]
+    fn test_struct_groups() {
+        let input: DeriveInput = parse_quote! {
+            struct Entity {
+                #[easy_hash(group = "physics", group = "net")]
+                position: f32,
+                #[easy_hash(group = "net", skip_if_default)]
+                owner: u32,
+                name: String,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Entity {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Entity>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Entity {}",
+                    [
+                        easy_hash::schema::compose(
+                            "position #[easy_hash(group = \"physics\", group = \"net\")]",
+                            [
+                                easy_hash::schema::of::<f32>()
+                            ]
+                        ),
+                        easy_hash::schema::compose(
+                            "owner #[easy_hash(skip_if_default, group = \"net\")]",
+                            [
+                                easy_hash::schema::of::<u32>()
+                            ]
+                        ),
+                        easy_hash::schema::compose("name", [easy_hash::schema::of::<String>()])
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    let mut hashes = [0u64; 3usize];
+                    let mut len = 0usize;
+                    hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.position);
+                    len += 1;
+                    if !(*&self.owner == <u32 as ::core::default::Default>::default()) {
+                        hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.owner);
+                        len += 1;
+                    }
+                    hashes[len] = easy_hash::EasyHash::ehash_in::<__F>(&self.name);
+                    len += 1;
+                    checksum.update(&easy_hash::u64_to_u32_slice(&hashes[..len]));
+                    __F::finish(checksum.value())
+                }
+
+                fn ehash_group(&self, group: &str) -> u64 {
+                    if !matches!(group, "physics" | "net") {
+                        return easy_hash::EasyHash::ehash(self);
+                    }
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    checksum.update(&[Self::TYPE_SALT]);
+                    let mut hashes = [0u64; 2usize];
+                    let mut len = 0usize;
+                    if matches!(group, "physics" | "net") {
+                        hashes[len] = easy_hash::EasyHash::ehash_group(&self.position, group);
+                        len += 1;
+                    }
+                    if matches!(group, "net")
+                        && !(*&self.owner == <u32 as ::core::default::Default>::default())
+                    {
+                        hashes[len] = easy_hash::EasyHash::ehash_group(&self.owner, group);
+                        len += 1;
+                    }
+                    checksum.update(&easy_hash::u64_to_u32_slice(&hashes[..len]));
                     checksum.value()
                 }
             }
@@ -378,7 +1441,159 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    fn test_remote_rejects_groups() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(remote = "physics::Body")]
+            struct BodyDef {
+                #[easy_hash(group = "physics")]
+                mass: f32,
+            }
+        };
+
+        let err = expand_derive(input, false, false).unwrap_err().to_string();
+        assert_eq!(err, "field groups are not supported on remote definitions");
+    }
+
+    #[test]
+    fn test_transparent_newtype() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(transparent)]
+            struct Meters(f32);
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Meters {
+                const TYPE_SALT: u32 = <f32 as easy_hash::EasyHash>::TYPE_SALT;
+                const TYPE_SALT64: u64 = <f32 as easy_hash::EasyHash>::TYPE_SALT64;
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Meters() #[easy_hash(transparent)]",
+                    [
+                        easy_hash::schema::compose("0", [easy_hash::schema::of::<f32>()])
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<__F>(&self.0)
+                }
+
+                fn ehash_group(&self, group: &str) -> u64 {
+                    easy_hash::EasyHash::ehash_group(&self.0, group)
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_transparent_rejects_multiple_fields() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(transparent)]
+            struct Position {
+                x: f32,
+                y: f32,
+            }
+        };
+
+        let err = expand_derive(input, false, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "#[easy_hash(transparent)] requires exactly one non-ignored field"
+        );
+    }
+
+    #[test]
+    fn test_remote_enum() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(remote = "other::Shape<f32>")]
+            enum ShapeDef {
+                Circle(f32),
+                Rect { w: f32, h: f32 },
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl ShapeDef {
+                pub const TYPE_SALT: u32 = easy_hash::type_salt::<other::Shape<f32> >();
+                pub const TYPE_SALT64: u64 = easy_hash::type_salt64::<other::Shape<f32> >();
+                pub const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "enum Shape",
+                    [
+                        easy_hash::schema::compose(
+                            "Circle()",
+                            [
+                                easy_hash::schema::compose("0", [easy_hash::schema::of::<f32>()])
+                            ]
+                        ),
+                        easy_hash::schema::compose(
+                            "Rect {}",
+                            [
+                                easy_hash::schema::compose("w", [easy_hash::schema::of::<f32>()]),
+                                easy_hash::schema::compose("h", [easy_hash::schema::of::<f32>()])
+                            ]
+                        )
+                    ]
+                );
+
+                pub fn ehash(remote: &other::Shape<f32>) -> u64 {
+                    Self::ehash_in::<easy_hash::v1::V1>(remote)
+                }
+
+                pub fn ehash_in<__F: easy_hash::HashFormat>(remote: &other::Shape<f32>) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    match remote {
+                        other::Shape::Circle(f0,) => {
+                            __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                            checksum.update(&[0]);
+                            checksum.update(&easy_hash::u64_to_u32_slice(&[
+                                easy_hash::EasyHash::ehash_in::<__F>(f0),
+                            ]));
+                        }
+                        other::Shape::Rect { w, h, } => {
+                            __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                            checksum.update(&[1]);
+                            checksum.update(&easy_hash::u64_to_u32_slice(&[
+                                easy_hash::EasyHash::ehash_in::<__F>(w),
+                                easy_hash::EasyHash::ehash_in::<__F>(h),
+                            ]));
+                        }
+                    }
+                    __F::finish(checksum.value())
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tuple_fields_respect_ignore() {
+        let input: DeriveInput = parse_quote! {
+            enum Example {
+                Tuple(u32, #[easy_hash_ignore] u64),
+            }
+        };
+
+        let actual = expand_as_string(input);
+        assert!(
+            actual.contains("Self :: Tuple (f0 , _ ,)"),
+            "ignored tuple field should not be bound: {actual}"
+        );
+        assert!(
+            !actual.contains("(f1)"),
+            "ignored tuple field should not be hashed: {actual}"
+        );
+    }
+
+    #[test]
     fn test_union_not_supported() {
         let input: DeriveInput = parse_quote! {
             union Example {
@@ -386,6 +1601,143 @@ mod tests {
             }
         };
 
-        let _ = expand_as_string(input);
+        let err = expand_derive(input, false, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "EasyHash cannot be derived for unions; add #[easy_hash(bytes)] to hash a `bytemuck::Pod` union by its bytes"
+        );
+    }
+
+    #[test]
+    fn test_bytes_union() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(bytes)]
+            union Bits {
+                int: u32,
+                float: f32,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Bits
+            where
+                Bits: easy_hash::bytemuck::Pod
+            {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Bits>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "union Bits #[easy_hash(bytes)]",
+                    [
+                        easy_hash::schema::compose("int", [easy_hash::schema::hash_str("u32")]),
+                        easy_hash::schema::compose("float", [easy_hash::schema::hash_str("f32")])
+                    ]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    easy_hash::bytemuck_slices::ehash_pod_slice_in::<__F, Self>(
+                        Self::TYPE_SALT,
+                        Self::TYPE_SALT64,
+                        ::core::slice::from_ref(self),
+                    )
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_versioned_struct() {
+        let input: DeriveInput = parse_quote! {
+            #[easy_hash(version = 2, previous(v1 = "Save::hash_v1"))]
+            struct Save {
+                gold: u64,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        let expected = quote! {
+            impl easy_hash::EasyHash for Save {
+                const TYPE_SALT: u32 = easy_hash::type_salt::<Save>();
+                const SCHEMA_HASH: u64 = easy_hash::schema::compose(
+                    "struct Save {} #[easy_hash(version = 2)]",
+                    [easy_hash::schema::compose("gold", [easy_hash::schema::of::<u64>()])]
+                );
+
+                fn ehash(&self) -> u64 {
+                    easy_hash::EasyHash::ehash_in::<easy_hash::v1::V1>(self)
+                }
+
+                fn ehash_in<__F: easy_hash::HashFormat>(&self) -> u64 {
+                    let mut checksum = easy_hash::fletcher::Fletcher64::new();
+                    checksum.update(&[2u32]);
+                    __F::update_salt(&mut checksum, Self::TYPE_SALT, Self::TYPE_SALT64);
+                    checksum.update(&easy_hash::u64_to_u32_slice(&[
+                        easy_hash::EasyHash::ehash_in::<__F>(&self.gold),
+                    ]));
+                    __F::finish(checksum.value())
+                }
+            }
+
+            impl easy_hash::EasyHashVersioned for Save {
+                const VERSION: u32 = 2u32;
+
+                fn ehash_as_version(&self, version: u32) -> ::core::option::Option<u64> {
+                    match version {
+                        2u32 => ::core::option::Option::Some(easy_hash::EasyHash::ehash(self)),
+                        1u32 => ::core::option::Option::Some(Save::hash_v1(self)),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unknown_field_option_is_rejected() {
+        let input: DeriveInput = parse_quote! {
+            struct Example {
+                #[easy_hash(skip_if_defualt)]
+                a: u32,
+            }
+        };
+
+        let err = expand_derive(input, false, false).unwrap_err().to_string();
+        assert!(
+            err.starts_with("unknown easy_hash field option `skip_if_defualt`; supported options:"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_schema_of_recursive_fields_uses_type_name() {
+        let input: DeriveInput = parse_quote! {
+            struct Node {
+                value: u32,
+                children: Vec<Node>,
+                parent: Option<Box<Self>>,
+            }
+        };
+
+        let actual = expand_as_string(input);
+        for expected in [
+            quote!(easy_hash::schema::of::<u32>()),
+            quote!(easy_hash::schema::hash_str("Vec<Node>")),
+            quote!(easy_hash::schema::hash_str("Option<Box<Self>>")),
+        ] {
+            let expected = expected.to_string();
+            assert!(
+                actual.contains(&expected),
+                "expected `{expected}` in {actual}"
+            );
+        }
     }
 }
@@ -0,0 +1,122 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{Data, Fields, Type};
+
+use crate::attr::{ContainerAttrs, FieldAttrs};
+use crate::parse_fields;
+
+/// Generate the `SCHEMA_HASH` of a type named `name` with the shape of `data`.
+///
+/// The fingerprint covers the kind of type, its name, its container options and,
+/// in order, every variant and field, including ignored ones: their names, their
+/// options and the schema of their types.
+pub(crate) fn expand_schema(
+    name: &str,
+    data: &Data,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<TokenStream> {
+    let context = Context {
+        name,
+        bytes: container_attrs.bytes.is_some(),
+    };
+    let (kind, parts) = match data {
+        Data::Struct(data_struct) => (
+            format!("struct {name}{}", shape(&data_struct.fields)),
+            context.fields(&data_struct.fields)?,
+        ),
+        Data::Enum(data_enum) => {
+            let variants = data_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    let description = format!("{}{}", variant.ident, shape(&variant.fields));
+                    let fields = context.fields(&variant.fields)?;
+                    Ok(quote! { easy_hash::schema::compose(#description, [#(#fields),*]) })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            (format!("enum {name}"), variants)
+        }
+        Data::Union(data_union) => {
+            let fields = parse_fields(&data_union.fields.named)?
+                .iter()
+                .map(|(f, attrs)| {
+                    let name = f
+                        .ident
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default();
+                    context.field(name, &f.ty, attrs)
+                })
+                .collect();
+            (format!("union {name}"), fields)
+        }
+    };
+    let description = format!("{kind}{}", container_attrs.describe());
+
+    Ok(quote! {
+        easy_hash::schema::compose(#description, [#(#parts),*])
+    })
+}
+
+/// The delimiters of a struct or variant, so that `S {}`, `S()` and `S` differ.
+fn shape(fields: &Fields) -> &'static str {
+    match fields {
+        Fields::Named(_) => " {}",
+        Fields::Unnamed(_) => "()",
+        Fields::Unit => "",
+    }
+}
+
+/// What the fields of a type are fingerprinted in.
+struct Context<'a> {
+    /// The name of the type.
+    name: &'a str,
+    /// Whether the type is `#[easy_hash(bytes)]`, whose fields need not implement
+    /// `EasyHash`.
+    bytes: bool,
+}
+
+impl Context<'_> {
+    fn fields(&self, fields: &Fields) -> syn::Result<Vec<TokenStream>> {
+        Ok(parse_fields(fields)?
+            .iter()
+            .enumerate()
+            .map(|(i, (f, attrs))| {
+                let name = f
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| i.to_string(), ToString::to_string);
+                self.field(name, &f.ty, attrs)
+            })
+            .collect())
+    }
+
+    /// Generate the fingerprint of a single field.
+    fn field(&self, name: String, ty: &Type, attrs: &FieldAttrs) -> TokenStream {
+        let description = format!("{name}{}", attrs.describe());
+        // Fields whose type does not implement `EasyHash`, or whose schema would depend
+        // on the schema being defined, are identified by their type as written.
+        let by_name = attrs.ignore || self.bytes || mentions(ty, self.name);
+        let ty_schema = match &attrs.with {
+            _ if by_name => {
+                let ty_name = quote!(#ty).to_string().replace(' ', "");
+                quote! { easy_hash::schema::hash_str(#ty_name) }
+            }
+            Some(with) => quote! { #with::SCHEMA_HASH },
+            None => quote! { easy_hash::schema::of::<#ty>() },
+        };
+        quote! { easy_hash::schema::compose(#description, [#ty_schema]) }
+    }
+}
+
+/// Whether `ty` refers to the type named `name`, directly or through `Self`.
+fn mentions(ty: &Type, name: &str) -> bool {
+    fn visit(tokens: TokenStream, name: &str) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == name || ident == "Self",
+            TokenTree::Group(group) => visit(group.stream(), name),
+            _ => false,
+        })
+    }
+    visit(quote!(#ty), name)
+}
@@ -0,0 +1,105 @@
+//! Hashes with `easy_hash` in a `no_std` crate, without `alloc`.
+//!
+//! This only compiles if the core of `easy_hash` (the trait, the derive, primitives,
+//! tuples, Pod arrays and the hash formats) works without `std`.
+
+#![cfg_attr(not(test), no_std)]
+
+use easy_hash::{EasyHash, EasyHashVersioned, HashBuilder, const_ehash, ehash};
+
+#[derive(EasyHash)]
+pub struct Body {
+    pub id: u32,
+    pub position: (i32, i32),
+    #[easy_hash(quantize = 0.01)]
+    pub mass: f32,
+    pub cells: [u8; 4],
+    #[easy_hash(skip_if_default)]
+    pub sleeping: bool,
+    pub parent: Option<u32>,
+}
+
+#[derive(EasyHash)]
+pub enum Event {
+    Spawn(Body),
+    Despawn { id: u32 },
+    Tick,
+}
+
+#[derive(EasyHash)]
+#[easy_hash(transparent)]
+pub struct Meters(pub f32);
+
+#[derive(EasyHash)]
+#[easy_hash(version = 2)]
+pub struct Header {
+    pub frame: u64,
+    pub name: &'static str,
+}
+
+pub const SPAWN_EVENT: u64 = const_ehash!(("spawn", 3u32));
+
+/// The hash of a frame of events, as a simulation would compute it.
+pub fn frame_hash(frame: u64, events: &[Event]) -> u64 {
+    events
+        .iter()
+        .fold(HashBuilder::new().field(&frame), |builder, event| {
+            builder.field(event)
+        })
+        .finish()
+}
+
+pub fn header_hashes(header: &Header) -> [Option<u64>; 3] {
+    [
+        header.ehash_as_version(1),
+        header.ehash_as_version(2),
+        Some(ehash!(header.frame, header.name)),
+    ]
+}
+
+pub fn v2_hash(body: &Body) -> u64 {
+    body.ehash_in::<easy_hash::v2::V2>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body() -> Body {
+        Body {
+            id: 7,
+            position: (-1, 2),
+            mass: 1.5,
+            cells: [1, 2, 3, 4],
+            sleeping: false,
+            parent: None,
+        }
+    }
+
+    #[test]
+    fn test_hashes_match_pinned_values() {
+        // From `easy_hash::stability`, which needs `alloc`.
+        assert_eq!(7u32.ehash(), 0x0be6_e969_05f3_74b8);
+        assert_eq!((-1i64).ehash(), 0xe107_ac01_8d36_44cd);
+        assert_eq!(true.ehash(), 0x9129_2a7c_c894_953e);
+    }
+
+    #[test]
+    fn test_core_features() {
+        assert_eq!(SPAWN_EVENT, ("spawn", 3u32).ehash());
+        assert_ne!(body().ehash(), v2_hash(&body()));
+
+        let events = [Event::Spawn(body()), Event::Despawn { id: 7 }, Event::Tick];
+        assert_ne!(frame_hash(1, &events), frame_hash(2, &events));
+        assert_eq!(Meters(2.0).ehash(), 2.0f32.ehash());
+
+        let header = Header {
+            frame: 3,
+            name: "main",
+        };
+        let [v1, v2, fields] = header_hashes(&header);
+        assert_eq!(v1, None);
+        assert_eq!(v2, Some(header.ehash()));
+        assert_ne!(fields, v2);
+    }
+}